@@ -1,5 +1,6 @@
 pub mod ltl_to_buechi;
 pub mod product;
+pub mod reduction;
 pub(crate) mod transitions;
 
 use crate::buechi::transitions::Transitions;
@@ -56,7 +57,7 @@ where
     pub fn from_generalized_büchi(generalized_büchi: GeneralizedBüchi<T>) -> Büchi<(T, u8)> {
         let amount_endsets = generalized_büchi.end_sets.len();
         if amount_endsets == 0 {
-            return Büchi {
+            Büchi {
                 state_infos: generalized_büchi
                     .state_infos
                     .iter()
@@ -66,9 +67,9 @@ where
                 start_state: generalized_büchi.start_state,
                 transitions: generalized_büchi.transitions.clone(),
                 end_set: BitVec::from_elem(generalized_büchi.state_infos.len(), true),
-            };
+            }
         } else if amount_endsets == 1 {
-            return Büchi {
+            Büchi {
                 state_infos: generalized_büchi
                     .state_infos
                     .iter()
@@ -78,11 +79,11 @@ where
                 start_state: generalized_büchi.start_state,
                 transitions: generalized_büchi.transitions.clone(),
                 end_set: generalized_büchi.end_sets.first().unwrap().clone(),
-            };
+            }
         } else {
-            let mut infos =
-                Vec::<(T, u8)>::with_capacity(generalized_büchi.state_infos.len() * amount_endsets);
-            let mut transitions = Transitions::for_states(infos.len());
+            let amount_states = generalized_büchi.state_infos.len();
+            let mut infos = Vec::<(T, u8)>::with_capacity(amount_states * amount_endsets);
+            let mut transitions = Transitions::for_states(amount_states * amount_endsets);
             for i in 0..amount_endsets {
                 for state_info in generalized_büchi.state_infos.iter() {
                     infos.push((state_info.clone(), i as u8));
@@ -100,27 +101,24 @@ where
                         i
                     };
                     transitions.add(
-                        (i * generalized_büchi.state_infos.len()) as u64 + state1,
+                        (i * amount_states) as u64 + state1,
                         symbol,
-                        (target_plane * generalized_büchi.state_infos.len()) as u64 + state2,
+                        (target_plane * amount_states) as u64 + state2,
                     )
                 }
             }
-            let mut end_set = BitVec::from_elem(
-                generalized_büchi.end_sets.len() * (amount_endsets - 1),
-                false,
-            );
-            end_set.reserve_exact(generalized_büchi.end_sets.len());
+            let mut end_set = BitVec::from_elem(amount_states * (amount_endsets - 1), false);
+            end_set.reserve_exact(amount_states);
             for b in generalized_büchi.end_sets.last().unwrap() {
                 end_set.push(b);
             }
-            return Büchi {
+            Büchi {
                 state_infos: infos,
                 amount_aps: generalized_büchi.amount_aps,
                 start_state: generalized_büchi.start_state,
                 transitions,
                 end_set,
-            };
+            }
         }
     }
 
@@ -128,15 +126,68 @@ where
         self.state_infos.len() as u64
     }
 
+    pub fn start_state(&self) -> State {
+        self.start_state
+    }
+
+    pub fn is_accepting(&self, state: State) -> bool {
+        self.end_set.get(state as usize).unwrap()
+    }
+
+    pub fn successors_with_symbol(
+        &self,
+        state: State,
+        symbol: Symbol,
+    ) -> impl Iterator<Item = State> + '_ {
+        self.transitions.get_from_state_with_symbol(state, symbol)
+    }
+
+    /// States from which an accepting run starts, i.e. that reach an accepting state on a
+    /// cycle.
+    pub fn non_empty_states(&self) -> BitVec {
+        let amount_states = self.amount_states() as usize;
+        let mut predecessors = vec![vec![]; amount_states];
+        for (from, _, to) in self.transitions.get_all() {
+            predecessors[to as usize].push(from);
+        }
+        // Walks backwards from the states in `stack` and marks every state reached.
+        let mark_predecessors = |stack: &mut Vec<State>, marked: &mut BitVec| {
+            while let Some(state) = stack.pop() {
+                for from in &predecessors[state as usize] {
+                    if !marked.get(*from as usize).unwrap() {
+                        marked.set(*from as usize, true);
+                        stack.push(*from);
+                    }
+                }
+            }
+        };
+
+        let mut non_empty = BitVec::from_elem(amount_states, false);
+        let mut stack = vec![];
+        for state in 0..amount_states as State {
+            if !self.is_accepting(state) {
+                continue;
+            }
+            let mut reaching = BitVec::from_elem(amount_states, false);
+            mark_predecessors(&mut vec![state], &mut reaching);
+            if reaching.get(state as usize).unwrap() {
+                non_empty.set(state as usize, true);
+                stack.push(state);
+            }
+        }
+        mark_predecessors(&mut stack, &mut non_empty);
+        non_empty
+    }
+
     fn dfs_cycle(&self, s: &mut EmptinessStruct, q: State) -> bool {
         s.inner.set(q as usize, true);
         for qnext in self.transitions.get_next_states_from_state(q) {
-            if !s.outer_finished.get(qnext as usize).unwrap() {
+            // States that are not finished yet lie on the outer DFS stack and close a cycle.
+            if !s.outer_finished.get(qnext as usize).unwrap()
+                || !s.inner.get(qnext as usize).unwrap() && self.dfs_cycle(s, qnext)
+            {
                 s.stack.push(qnext);
                 return true;
-            } else if !s.inner.get(qnext as usize).unwrap() {
-                self.dfs_cycle(s, qnext);
-                return true;
             }
         }
         false
@@ -166,7 +217,7 @@ where
             outer_finished: BitVec::from_elem(self.amount_states() as usize, false),
         };
 
-        return if self.dfs(&mut state, self.start_state) {
+        if self.dfs(&mut state, self.start_state) {
             Some(
                 state
                     .stack
@@ -176,6 +227,108 @@ where
             )
         } else {
             None
-        };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Automaton over one proposition with the states `0 -> 1 -> 2 -> 0` and the accepting
+    /// sets `{0}` and `{1, 2}`.
+    fn generalized() -> GeneralizedBüchi<char> {
+        let mut transitions = Transitions::for_states(3);
+        for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+            transitions.add(from, 0, to);
+            transitions.add(from, 1, to);
+        }
+        GeneralizedBüchi {
+            state_infos: vec!['a', 'b', 'c'],
+            amount_aps: 1,
+            start_state: 0,
+            transitions,
+            end_sets: vec![
+                BitVec::from_iter([true, false, false]),
+                BitVec::from_iter([false, true, true]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_degeneralized_transitions() {
+        let büchi = Büchi::from_generalized_büchi(generalized());
+        assert_eq!(büchi.amount_states(), 6);
+        assert_eq!(
+            büchi
+                .transitions
+                .get_from_state_with_symbol(0, 0)
+                .collect::<Vec<_>>(),
+            [4]
+        );
+        assert_eq!(
+            büchi
+                .transitions
+                .get_from_state_with_symbol(4, 1)
+                .collect::<Vec<_>>(),
+            [2]
+        );
+        assert_eq!(
+            büchi
+                .transitions
+                .get_from_state_with_symbol(2, 0)
+                .collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(
+            büchi
+                .transitions
+                .get_from_state_with_symbol(3, 1)
+                .collect::<Vec<_>>(),
+            [4]
+        );
+        assert_eq!(
+            büchi
+                .transitions
+                .get_from_state_with_symbol(5, 0)
+                .collect::<Vec<_>>(),
+            [0]
+        );
+    }
+
+    #[test]
+    fn test_degeneralized_accepting_states() {
+        let büchi = Büchi::from_generalized_büchi(generalized());
+        assert_eq!(büchi.end_set.len(), 6);
+        let accepting: Vec<State> = (0..6)
+            .filter(|q| büchi.end_set.get(*q as usize).unwrap())
+            .collect();
+        assert_eq!(accepting, [4, 5]);
+        assert!(büchi.get_loop().is_some());
+    }
+
+    #[test]
+    fn test_loop_needs_cycle() {
+        // The accepting start state reaches a finished state from which it is not reachable.
+        let mut transitions = Transitions::for_states(2);
+        transitions.add(0, 0, 1);
+        let büchi = Büchi::new(
+            vec!['a', 'b'],
+            1,
+            0,
+            transitions.clone(),
+            BitVec::from_iter([true, false]),
+        );
+        assert!(büchi.get_loop().is_none());
+
+        transitions.add(1, 0, 0);
+        let büchi = Büchi::new(
+            vec!['a', 'b'],
+            1,
+            0,
+            transitions,
+            BitVec::from_iter([true, false]),
+        );
+        assert!(büchi.get_loop().is_some());
     }
 }
@@ -1,38 +1,23 @@
-use crate::buechi::{GeneralizedBüchi, State, Transitions};
+use crate::buechi::reduction::ReductionStatistics;
+use crate::buechi::{Büchi, GeneralizedBüchi, State, Transitions};
 use crate::parsing::LTLFormula;
 use bit_vec::BitVec;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::ops::{AddAssign, BitOr, BitOrAssign};
 
-fn get_aps_in_ltl(ltl: &LTLFormula, aps: &mut HashSet<u8>) {
-    match ltl {
-        LTLFormula::AP(id) => {
-            aps.insert(*id);
-        }
-        LTLFormula::Not(phi) => {
-            get_aps_in_ltl(phi, aps);
-        }
-        LTLFormula::And(phi1, phi2) => {
-            get_aps_in_ltl(phi1, aps);
-            get_aps_in_ltl(phi2, aps);
-        }
-        LTLFormula::Next(phi) => {
-            get_aps_in_ltl(phi, aps);
-        }
-        LTLFormula::Until(_, phi1, phi2) => {
-            get_aps_in_ltl(phi1, aps);
-            get_aps_in_ltl(phi2, aps);
-        }
-    }
-}
-
-pub fn ltl_to_büchi(ltl: &LTLFormula) -> GeneralizedBüchi<u64> {
-    let mut aps_set = HashSet::<u8>::new();
-    get_aps_in_ltl(ltl, &mut aps_set);
-    let ap_count = aps_set.len() as u8;
+/// Generalized Büchi automaton of `ltl` over the propositions `0..amount_aps`, which include
+/// all propositions of `ltl`.
+pub fn ltl_to_büchi(ltl: &LTLFormula, amount_aps: u8) -> GeneralizedBüchi<u64> {
+    let ap_count = amount_aps;
     let ap_bitmask = (1u64 << ap_count) - 1;
 
+    // Propositions that do not occur in `ltl` may have either value.
     let mut states = vec![0];
+    let occurring = ltl.aps();
+    for ap in (0..amount_aps).filter(|ap| !occurring.contains(ap)) {
+        let with_ap: Vec<State> = states.iter().map(|state| state | 1 << ap).collect();
+        states.extend(with_ap);
+    }
 
     let mut constraints = Vec::<CompareTheThing>::new();
     let mut end_set_functions = Vec::<GetValueForThing>::new();
@@ -87,6 +72,16 @@ pub fn ltl_to_büchi(ltl: &LTLFormula) -> GeneralizedBüchi<u64> {
     }
 }
 
+/// Büchi automaton of `ltl` over the propositions `0..amount_aps`, reduced before it is
+/// degeneralized, together with the statistics of the reduction.
+pub fn ltl_to_reduced_büchi(
+    ltl: &LTLFormula,
+    amount_aps: u8,
+) -> (Büchi<(u64, u8)>, ReductionStatistics) {
+    let (generalized_büchi, statistics) = ltl_to_büchi(ltl, amount_aps).reduce();
+    (Büchi::from_generalized_büchi(generalized_büchi), statistics)
+}
+
 #[derive(Clone)]
 enum GetValueForThing {
     And(Box<GetValueForThing>, Box<GetValueForThing>),
@@ -238,6 +233,14 @@ fn ltl_to_büchi_recursive<'a>(
                     Box::new(GetValueForThing::Lookup(id)),
                     Box::new(GetValueForThing::Not(Box::new(val2))),
                 ))));
+            } else {
+                // A violated weak until must eventually be refuted by !phi1.
+                end_set_functions.push(GetValueForThing::Not(Box::new(GetValueForThing::And(
+                    Box::new(GetValueForThing::Not(Box::new(GetValueForThing::Lookup(
+                        id,
+                    )))),
+                    Box::new(val1),
+                ))));
             }
             GetValueForThing::Lookup(id)
         }
@@ -0,0 +1,570 @@
+use crate::buechi::transitions::Transitions;
+use crate::buechi::{GeneralizedBüchi, State, Symbol};
+use bit_vec::BitVec;
+use std::collections::VecDeque;
+
+/// Sizes of an automaton before and after it was reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReductionStatistics {
+    pub states_before: usize,
+    pub states_after: usize,
+    pub transitions_before: usize,
+    pub transitions_after: usize,
+    pub acceptance_sets_before: usize,
+    pub acceptance_sets_after: usize,
+    /// States that are unreachable or cannot reach an accepting cycle.
+    pub useless_states: usize,
+    /// States that were merged into a state simulating them and simulated by them.
+    pub merged_states: usize,
+}
+
+/// Automaton with state-based generalized acceptance, in the form the reduction works on.
+struct Automaton {
+    start: usize,
+    successors: Vec<Vec<(Symbol, usize)>>,
+    acceptance: Vec<BitVec>,
+}
+
+impl Automaton {
+    fn new(
+        start: State,
+        transitions: &Transitions,
+        amount_states: usize,
+        acceptance: Vec<BitVec>,
+    ) -> Self {
+        let successors = (0..amount_states)
+            .map(|state| {
+                let mut successors: Vec<(Symbol, usize)> = transitions
+                    .get_from_state(state as State)
+                    .map(|(symbol, to)| (symbol, to as usize))
+                    .collect();
+                successors.sort_unstable();
+                successors
+            })
+            .collect();
+        Automaton {
+            start: start as usize,
+            successors,
+            acceptance,
+        }
+    }
+
+    fn amount_transitions(&self) -> usize {
+        self.successors.iter().map(Vec::len).sum()
+    }
+
+    fn transitions(&self) -> Transitions {
+        let mut transitions = Transitions::for_states(self.successors.len());
+        for (from, successors) in self.successors.iter().enumerate() {
+            for (symbol, to) in successors {
+                transitions.add(from as State, *symbol, *to as State);
+            }
+        }
+        transitions
+    }
+
+    /// Maps every state to a state of the reduced automaton or to `None` if it is removed, and
+    /// rebuilds the automaton accordingly. Returns the original state of every new state.
+    fn restrict(&mut self, map: &[Option<usize>]) -> Vec<usize> {
+        let amount_states = map.iter().flatten().max().map_or(0, |state| state + 1);
+        let mut originals = vec![usize::MAX; amount_states];
+        let mut successors = vec![vec![]; amount_states];
+        let mut acceptance = vec![BitVec::from_elem(amount_states, false); self.acceptance.len()];
+        for (state, new_state) in map.iter().enumerate() {
+            let Some(new_state) = new_state else {
+                continue;
+            };
+            if originals[*new_state] == usize::MAX {
+                originals[*new_state] = state;
+                for (set, new_set) in self.acceptance.iter().zip(&mut acceptance) {
+                    new_set.set(*new_state, set.get(state).unwrap());
+                }
+            }
+            for (symbol, to) in &self.successors[state] {
+                if let Some(to) = map[*to] {
+                    successors[*new_state].push((*symbol, to));
+                }
+            }
+        }
+        for successors in &mut successors {
+            successors.sort_unstable();
+            successors.dedup();
+        }
+        self.start = map[self.start].unwrap();
+        self.successors = successors;
+        self.acceptance = acceptance;
+        originals
+    }
+
+    /// Removes all states that are unreachable or cannot reach a cycle visiting every acceptance
+    /// set. The start state is always kept.
+    fn remove_useless_states(&mut self) -> Vec<usize> {
+        let amount_states = self.successors.len();
+        let mut reachable = vec![false; amount_states];
+        reachable[self.start] = true;
+        let mut stack = vec![self.start];
+        while let Some(state) = stack.pop() {
+            for (_, to) in &self.successors[state] {
+                if !reachable[*to] {
+                    reachable[*to] = true;
+                    stack.push(*to);
+                }
+            }
+        }
+
+        let components = strongly_connected_components(&self.successors);
+        let amount_components = components.iter().max().map_or(0, |c| c + 1);
+        let mut nontrivial = vec![false; amount_components];
+        let mut visited_sets =
+            vec![BitVec::from_elem(self.acceptance.len(), false); amount_components];
+        for (state, successors) in self.successors.iter().enumerate() {
+            let component = components[state];
+            if successors
+                .iter()
+                .any(|(_, to)| components[*to] == component)
+            {
+                nontrivial[component] = true;
+            }
+            for (i, set) in self.acceptance.iter().enumerate() {
+                if set.get(state).unwrap() {
+                    visited_sets[component].set(i, true);
+                }
+            }
+        }
+
+        let mut predecessors = vec![vec![]; amount_states];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for (_, to) in successors {
+                predecessors[*to].push(from);
+            }
+        }
+        let mut useful = vec![false; amount_states];
+        let mut stack: Vec<usize> = (0..amount_states)
+            .filter(|state| {
+                let component = components[*state];
+                nontrivial[component] && visited_sets[component].all()
+            })
+            .collect();
+        for state in &stack {
+            useful[*state] = true;
+        }
+        while let Some(state) = stack.pop() {
+            for from in &predecessors[state] {
+                if !useful[*from] {
+                    useful[*from] = true;
+                    stack.push(*from);
+                }
+            }
+        }
+
+        let mut next = 0;
+        let map: Vec<Option<usize>> = (0..amount_states)
+            .map(|state| {
+                (state == self.start || reachable[state] && useful[state]).then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+        self.restrict(&map)
+    }
+
+    /// Removes acceptance sets that contain every state on a cycle or that are visited
+    /// infinitely often whenever another set is.
+    fn remove_redundant_acceptance_sets(&mut self) {
+        let components = strongly_connected_components(&self.successors);
+        let mut on_cycle = BitVec::from_elem(self.successors.len(), false);
+        for (state, successors) in self.successors.iter().enumerate() {
+            if successors
+                .iter()
+                .any(|(_, to)| components[*to] == components[state])
+            {
+                on_cycle.set(state, true);
+            }
+        }
+        // Only states on cycles can be visited infinitely often.
+        let relevant: Vec<BitVec> = self
+            .acceptance
+            .iter()
+            .map(|set| {
+                let mut set = set.clone();
+                set.and(&on_cycle);
+                set
+            })
+            .collect();
+
+        let mut keep = vec![true; self.acceptance.len()];
+        for i in 0..relevant.len() {
+            if relevant[i] == on_cycle {
+                keep[i] = false;
+                continue;
+            }
+            // A superset of another set is visited whenever the other set is. Of equal sets,
+            // the first one is kept.
+            keep[i] = !(0..relevant.len()).any(|j| {
+                j != i
+                    && keep[j]
+                    && is_subset(&relevant[j], &relevant[i])
+                    && (relevant[j] != relevant[i] || j < i)
+            });
+        }
+
+        let mut index = 0;
+        self.acceptance.retain(|_| {
+            index += 1;
+            keep[index - 1]
+        });
+    }
+
+    /// Computes the direct simulation: `r` simulates `q` if `r` is in every acceptance set
+    /// containing `q` and every transition of `q` can be answered by a transition of `r` with
+    /// the same symbol to a state simulating the target. Returns `simulated_by`, where
+    /// `simulated_by[q][r]` holds if `r` simulates `q`.
+    fn direct_simulation(&self) -> Vec<BitVec> {
+        let amount_states = self.successors.len();
+        let mut predecessors = vec![vec![]; amount_states];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for (symbol, to) in successors {
+                predecessors[*to].push((*symbol, from));
+            }
+        }
+
+        let mut simulated_by: Vec<BitVec> = (0..amount_states)
+            .map(|q| {
+                let mut row = BitVec::from_elem(amount_states, true);
+                for set in &self.acceptance {
+                    if set.get(q).unwrap() {
+                        row.and(set);
+                    }
+                }
+                row
+            })
+            .collect();
+
+        // Whenever the states simulating a state shrink, its predecessors have to be checked
+        // again.
+        let mut queued = vec![true; amount_states];
+        let mut queue: VecDeque<usize> = (0..amount_states).collect();
+        while let Some(q) = queue.pop_front() {
+            queued[q] = false;
+            let mut row = simulated_by[q].clone();
+            for (symbol, q_next) in &self.successors[q] {
+                // States with a transition labelled `symbol` into a state simulating `q_next`.
+                let mut answering = BitVec::from_elem(amount_states, false);
+                for (r_next, simulates) in simulated_by[*q_next].iter().enumerate() {
+                    if simulates {
+                        for (other, r) in &predecessors[r_next] {
+                            if other == symbol {
+                                answering.set(*r, true);
+                            }
+                        }
+                    }
+                }
+                row.and(&answering);
+            }
+            if row != simulated_by[q] {
+                simulated_by[q] = row;
+                for (_, from) in &predecessors[q] {
+                    if !queued[*from] {
+                        queued[*from] = true;
+                        queue.push_back(*from);
+                    }
+                }
+            }
+        }
+        simulated_by
+    }
+
+    /// Merges states that simulate each other. Returns the number of merged states and the
+    /// original state of every new state.
+    fn merge_simulation_equivalent_states(&mut self) -> (usize, Vec<usize>) {
+        let simulated_by = self.direct_simulation();
+        let amount_states = self.successors.len();
+        let mut map = vec![None; amount_states];
+        let mut next = 0;
+        for q in 0..amount_states {
+            let representative = (0..q)
+                .find(|r| simulated_by[q].get(*r).unwrap() && simulated_by[*r].get(q).unwrap());
+            map[q] = match representative {
+                Some(r) => map[r],
+                None => {
+                    next += 1;
+                    Some(next - 1)
+                }
+            };
+        }
+        (amount_states - next, self.restrict(&map))
+    }
+
+    /// Applies all reductions. Returns the statistics and the original state of every new
+    /// state.
+    fn reduce(&mut self) -> (ReductionStatistics, Vec<usize>) {
+        let states_before = self.successors.len();
+        let transitions_before = self.amount_transitions();
+        let acceptance_sets_before = self.acceptance.len();
+
+        let pruned = self.remove_useless_states();
+        let useless_states = states_before - pruned.len();
+        self.remove_redundant_acceptance_sets();
+        let (merged_states, merged) = self.merge_simulation_equivalent_states();
+        let originals = merged.iter().map(|state| pruned[*state]).collect();
+
+        let statistics = ReductionStatistics {
+            states_before,
+            states_after: self.successors.len(),
+            transitions_before,
+            transitions_after: self.amount_transitions(),
+            acceptance_sets_before,
+            acceptance_sets_after: self.acceptance.len(),
+            useless_states,
+            merged_states,
+        };
+        (statistics, originals)
+    }
+}
+
+fn is_subset(subset: &BitVec, superset: &BitVec) -> bool {
+    let mut difference = subset.clone();
+    difference.difference(superset);
+    difference.none()
+}
+
+/// Component of every state, computed with Tarjan's algorithm. Components are numbered in
+/// reverse topological order.
+fn strongly_connected_components(successors: &[Vec<(Symbol, usize)>]) -> Vec<usize> {
+    let amount_states = successors.len();
+    let mut index = vec![usize::MAX; amount_states];
+    let mut low_link = vec![0; amount_states];
+    let mut on_stack = vec![false; amount_states];
+    let mut components = vec![usize::MAX; amount_states];
+    let mut stack = vec![];
+    let mut next_index = 0;
+    let mut next_component = 0;
+
+    for root in 0..amount_states {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // Call stack of the recursive formulation: a state and its next successor to visit.
+        let mut calls = vec![(root, 0)];
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((state, position)) = calls.pop() {
+            if let Some((_, to)) = successors[state].get(position) {
+                calls.push((state, position + 1));
+                if index[*to] == usize::MAX {
+                    index[*to] = next_index;
+                    low_link[*to] = next_index;
+                    next_index += 1;
+                    stack.push(*to);
+                    on_stack[*to] = true;
+                    calls.push((*to, 0));
+                } else if on_stack[*to] {
+                    low_link[state] = low_link[state].min(index[*to]);
+                }
+                continue;
+            }
+
+            if low_link[state] == index[state] {
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    components[member] = next_component;
+                    if member == state {
+                        break;
+                    }
+                }
+                next_component += 1;
+            }
+            if let Some((parent, _)) = calls.last() {
+                low_link[*parent] = low_link[*parent].min(low_link[state]);
+            }
+        }
+    }
+    components
+}
+
+impl<T: Clone> GeneralizedBüchi<T> {
+    /// Removes useless states, redundant acceptance sets and states that are equivalent under
+    /// direct simulation. The language of the automaton does not change.
+    pub fn reduce(&self) -> (GeneralizedBüchi<T>, ReductionStatistics) {
+        let mut automaton = Automaton::new(
+            self.start_state,
+            &self.transitions,
+            self.state_infos.len(),
+            self.end_sets.clone(),
+        );
+        let (statistics, originals) = automaton.reduce();
+        let reduced = GeneralizedBüchi {
+            state_infos: originals
+                .iter()
+                .map(|state| self.state_infos[*state].clone())
+                .collect(),
+            amount_aps: self.amount_aps,
+            start_state: automaton.start as State,
+            transitions: automaton.transitions(),
+            end_sets: automaton.acceptance,
+        };
+        (reduced, statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buechi::ltl_to_buechi::ltl_to_büchi;
+    use crate::buechi::Büchi;
+    use crate::parsing::parse;
+
+    impl<T: Clone> Büchi<T> {
+        /// Like [`GeneralizedBüchi::reduce`]. The checker reduces automata before they are
+        /// degeneralized instead, since computing the simulation of the larger degeneralized
+        /// automaton is expensive.
+        fn reduce(&self) -> (Büchi<T>, ReductionStatistics) {
+            let mut automaton = Automaton::new(
+                self.start_state,
+                &self.transitions,
+                self.state_infos.len(),
+                vec![self.end_set.clone()],
+            );
+            let (statistics, originals) = automaton.reduce();
+            let amount_states = originals.len();
+            let reduced = Büchi {
+                state_infos: originals
+                    .iter()
+                    .map(|state| self.state_infos[*state].clone())
+                    .collect(),
+                amount_aps: self.amount_aps,
+                start_state: automaton.start as State,
+                transitions: automaton.transitions(),
+                // Without acceptance sets, every state is accepting.
+                end_set: automaton
+                    .acceptance
+                    .pop()
+                    .unwrap_or_else(|| BitVec::from_elem(amount_states, true)),
+            };
+            (reduced, statistics)
+        }
+    }
+
+    /// Whether the automaton accepts the word `prefix cycle^ω`.
+    fn accepts<T: Clone>(büchi: &Büchi<T>, prefix: &[Symbol], cycle: &[Symbol]) -> bool {
+        let word: Vec<Symbol> = prefix.iter().chain(cycle).copied().collect();
+        let steps = |(state, position): (State, usize)| {
+            let next = if position + 1 < word.len() {
+                position + 1
+            } else {
+                prefix.len()
+            };
+            büchi
+                .transitions
+                .get_from_state_with_symbol(state, word[position])
+                .map(move |to| (to, next))
+                .collect::<Vec<_>>()
+        };
+        let reach = |from: Vec<(State, usize)>| {
+            let mut reached = from.clone();
+            let mut index = 0;
+            while index < reached.len() {
+                for to in steps(reached[index]) {
+                    if !reached.contains(&to) {
+                        reached.push(to);
+                    }
+                }
+                index += 1;
+            }
+            reached
+        };
+        reach(vec![(büchi.start_state, 0)])
+            .into_iter()
+            .filter(|(state, position)| *position >= prefix.len() && büchi.is_accepting(*state))
+            .any(|pair| reach(steps(pair)).contains(&pair))
+    }
+
+    /// All words `prefix cycle^ω` with a prefix of at most one and a cycle of at most three
+    /// symbols.
+    fn words(amount_aps: u8) -> Vec<(Vec<Symbol>, Vec<Symbol>)> {
+        let symbols: Vec<Symbol> = (0..1 << amount_aps).collect();
+        let mut sequences = vec![vec![]];
+        let mut last = vec![vec![]];
+        for _ in 0..3 {
+            last = last
+                .iter()
+                .flat_map(|sequence: &Vec<Symbol>| {
+                    symbols.iter().map(move |symbol| {
+                        let mut sequence = sequence.clone();
+                        sequence.push(*symbol);
+                        sequence
+                    })
+                })
+                .collect();
+            sequences.extend(last.clone());
+        }
+        let mut words = vec![];
+        for prefix in sequences.iter().filter(|prefix| prefix.len() <= 1) {
+            for cycle in sequences.iter().filter(|cycle| !cycle.is_empty()) {
+                words.push((prefix.clone(), cycle.clone()));
+            }
+        }
+        words
+    }
+
+    const FORMULAS: [&str; 10] = [
+        "a U b",
+        "G F a",
+        "F G a",
+        "G(a | X b)",
+        "!(a W b)",
+        "G F a & G F b",
+        "(a U b) U G a",
+        "X X a R b",
+        "F(a & X(b U a))",
+        "G F a & F G !a",
+    ];
+
+    #[test]
+    fn test_language_unchanged() {
+        for formula in FORMULAS {
+            let (ltl, ap_map) = parse::<u64>(formula).unwrap();
+            let generalized_büchi = ltl_to_büchi(&ltl, ap_map.len() as u8);
+            let (reduced, statistics) = generalized_büchi.reduce();
+            assert!(statistics.states_after <= statistics.states_before);
+            let büchi = Büchi::from_generalized_büchi(generalized_büchi);
+            let reduced = Büchi::from_generalized_büchi(reduced);
+            let (twice_reduced, _) = reduced.reduce();
+            for (prefix, cycle) in words(ap_map.len() as u8) {
+                let expected = accepts(&büchi, &prefix, &cycle);
+                assert_eq!(accepts(&reduced, &prefix, &cycle), expected, "{}", formula);
+                assert_eq!(
+                    accepts(&twice_reduced, &prefix, &cycle),
+                    expected,
+                    "{}",
+                    formula
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        let (ltl, ap_map) = parse::<u64>("G F a & F G !a").unwrap();
+        let (reduced, statistics) = ltl_to_büchi(&ltl, ap_map.len() as u8).reduce();
+        // The language is empty, only the start state remains.
+        assert_eq!(statistics.states_after, 1);
+        assert_eq!(statistics.transitions_after, 0);
+        assert_eq!(reduced.state_infos.len(), 1);
+        assert!(reduced.transitions.get_all().next().is_none());
+
+        let (ltl, ap_map) = parse::<u64>("G F a & G F b").unwrap();
+        let (_, statistics) = ltl_to_büchi(&ltl, ap_map.len() as u8).reduce();
+        assert!(statistics.useless_states > 0);
+        assert!(statistics.merged_states > 0);
+        assert!(statistics.states_after < statistics.states_before);
+        assert!(statistics.acceptance_sets_after < statistics.acceptance_sets_before);
+    }
+}
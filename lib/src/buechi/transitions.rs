@@ -19,33 +19,6 @@ impl Transitions {
         a.get_mut(&to_state).unwrap().push(symbol);
     }
 
-    pub fn has(&self, from_state: &State, symbol: &Symbol, to_state: &State) -> bool {
-        let a = self.transitions.get(*from_state as usize).unwrap();
-        let option = a.get(to_state);
-        if let Some(state) = option {
-            state.contains(symbol)
-        } else {
-            false
-        }
-    }
-
-    pub fn get_symbols_from_to(
-        &self,
-        from_state: &State,
-        to_state: &State,
-    ) -> impl Iterator<Item = &Symbol> {
-        let vec = self
-            .transitions
-            .get(*from_state as usize)
-            .unwrap()
-            .get(to_state);
-        return if let Some(vec) = vec {
-            vec.iter()
-        } else {
-            [].iter()
-        };
-    }
-
     pub fn get_next_states_from_state(
         &self,
         from_state: State,
@@ -53,8 +26,8 @@ impl Transitions {
         self.transitions
             .get(from_state as usize)
             .unwrap()
-            .iter()
-            .map(|(a, _)| *a)
+            .keys()
+            .copied()
     }
 
     pub fn get_from_state(&self, from_state: State) -> impl Iterator<Item = (Symbol, State)> + '_ {
@@ -81,9 +54,9 @@ impl Transitions {
     }
 
     pub fn get_all(&self) -> impl Iterator<Item = (State, Symbol, State)> + '_ {
-        return (0..self.transitions.len() as u64).flat_map(move |from_state| {
+        (0..self.transitions.len() as u64).flat_map(move |from_state| {
             self.get_from_state(from_state)
                 .map(move |(symbol, to_state)| (from_state, symbol, to_state))
-        });
+        })
     }
 }
@@ -0,0 +1,240 @@
+use crate::{KripkeBuilder, ModelCheckingError};
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Interleaving composition: in every step, one component moves and all others stay.
+///
+/// With `synchronise`, actions that occur in more than one component are taken jointly: all
+/// components with such an action in their transitions have to take it in the same step.
+///
+/// States of the result are the reachable tuples of component states, given as one id per
+/// component, and are labelled with the union of the component labels.
+pub fn compose_asynchronous<Id: Hash + Eq + Clone>(
+    components: &[KripkeBuilder<Id>],
+    synchronise: bool,
+) -> Result<KripkeBuilder<Vec<Id>>, ModelCheckingError<Id>> {
+    let composition = Composition::new(components, synchronise)?;
+    Ok(composition.explore(|state| composition.asynchronous_successors(state)))
+}
+
+/// Synchronous composition: in every step, all components move at once. A state in which one
+/// component deadlocks is a deadlock of the composition.
+///
+/// With `synchronise`, a step in which a component takes an action that occurs in more than
+/// one component is only possible if all components with that action take it. Since a
+/// transition carries at most one action, a step with several different actions is added as
+/// one transition per action.
+///
+/// States are built as in [`compose_asynchronous`].
+pub fn compose_synchronous<Id: Hash + Eq + Clone>(
+    components: &[KripkeBuilder<Id>],
+    synchronise: bool,
+) -> Result<KripkeBuilder<Vec<Id>>, ModelCheckingError<Id>> {
+    let composition = Composition::new(components, synchronise)?;
+    Ok(composition.explore(|state| composition.synchronous_successors(state)))
+}
+
+struct Composition<'a, Id> {
+    components: &'a [KripkeBuilder<Id>],
+    /// For every synchronised action, the components whose transitions contain it.
+    owners: Vec<(String, Vec<usize>)>,
+}
+
+impl<'a, Id: Hash + Eq + Clone> Composition<'a, Id> {
+    fn new(
+        components: &'a [KripkeBuilder<Id>],
+        synchronise: bool,
+    ) -> Result<Self, ModelCheckingError<Id>> {
+        for component in components {
+            if let Some(error) = component.validate().into_iter().next() {
+                return Err(error);
+            }
+        }
+
+        let mut owners = Vec::<(String, Vec<usize>)>::new();
+        if synchronise {
+            let alphabets: Vec<_> = components.iter().map(|c| c.actions()).collect();
+            let mut actions: Vec<&String> = alphabets.iter().flatten().collect();
+            actions.sort();
+            actions.dedup();
+            for action in actions {
+                let action_owners: Vec<usize> = (0..components.len())
+                    .filter(|i| alphabets[*i].contains(action))
+                    .collect();
+                if action_owners.len() > 1 {
+                    owners.push((action.clone(), action_owners));
+                }
+            }
+        }
+        Ok(Composition { components, owners })
+    }
+
+    fn owners_of(&self, action: Option<&str>) -> Option<&[usize]> {
+        let action = action?;
+        self.owners
+            .iter()
+            .find(|(owned, _)| owned == action)
+            .map(|(_, owners)| owners.as_slice())
+    }
+
+    fn asynchronous_successors(&self, state: &[Id]) -> Vec<(Vec<Id>, Option<String>)> {
+        let mut successors = vec![];
+        for (i, component) in self.components.iter().enumerate() {
+            for (to, action) in component.successors(&state[i]) {
+                match self.owners_of(action) {
+                    // Joint steps are only created once, for the first component taking part.
+                    Some(owners) if owners[0] == i => {
+                        let moves: Vec<Vec<(usize, Id)>> = owners
+                            .iter()
+                            .map(|j| {
+                                self.components[*j]
+                                    .successors(&state[*j])
+                                    .filter(|(_, other)| *other == action)
+                                    .map(|(to, _)| (*j, to.clone()))
+                                    .collect()
+                            })
+                            .collect();
+                        for joint_move in cartesian_product(&moves) {
+                            let mut next = state.to_vec();
+                            for (j, to) in joint_move {
+                                next[j] = to;
+                            }
+                            successors.push((next, action.map(str::to_string)));
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        let mut next = state.to_vec();
+                        next[i] = to.clone();
+                        successors.push((next, action.map(str::to_string)));
+                    }
+                }
+            }
+        }
+        successors
+    }
+
+    fn synchronous_successors(&self, state: &[Id]) -> Vec<(Vec<Id>, Option<String>)> {
+        let moves: Vec<Vec<(&Id, Option<&str>)>> = self
+            .components
+            .iter()
+            .enumerate()
+            .map(|(i, component)| component.successors(&state[i]).collect())
+            .collect();
+
+        let mut successors = vec![];
+        'steps: for step in cartesian_product(&moves) {
+            let mut actions = Vec::<&str>::new();
+            for (_, action) in &step {
+                if let Some(owners) = self.owners_of(*action) {
+                    if owners.iter().any(|j| step[*j].1 != *action) {
+                        continue 'steps;
+                    }
+                }
+                if let Some(action) = action {
+                    if !actions.contains(action) {
+                        actions.push(action);
+                    }
+                }
+            }
+            let next: Vec<Id> = step.iter().map(|(to, _)| (*to).clone()).collect();
+            if actions.is_empty() {
+                successors.push((next, None));
+            } else {
+                for action in actions {
+                    successors.push((next.clone(), Some(action.to_string())));
+                }
+            }
+        }
+        successors
+    }
+
+    /// Builds the reachable part of the composition, breadth first from the combinations of
+    /// start states.
+    fn explore(
+        &self,
+        successors: impl Fn(&[Id]) -> Vec<(Vec<Id>, Option<String>)>,
+    ) -> KripkeBuilder<Vec<Id>> {
+        let starts: Vec<Vec<Id>> = self
+            .components
+            .iter()
+            .map(|component| {
+                component
+                    .states()
+                    .filter(|(_, _, start)| *start)
+                    .map(|(id, _, _)| id.clone())
+                    .collect()
+            })
+            .collect();
+
+        let mut kripke_builder = KripkeBuilder::<Vec<Id>>::new();
+        let mut seen = HashSet::<Vec<Id>>::new();
+        let mut queue = VecDeque::<Vec<Id>>::new();
+        for start in cartesian_product(&starts) {
+            if seen.insert(start.clone()) {
+                kripke_builder.add_state(self.labels(&start), start.clone(), true);
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            for (next, action) in successors(&state) {
+                if seen.insert(next.clone()) {
+                    kripke_builder.add_state(self.labels(&next), next.clone(), false);
+                    queue.push_back(next.clone());
+                }
+                match action {
+                    Some(action) => {
+                        kripke_builder.add_labelled_transition(state.clone(), next, action)
+                    }
+                    None => kripke_builder.add_transition(state.clone(), next),
+                };
+            }
+        }
+        kripke_builder
+    }
+
+    fn labels(&self, state: &[Id]) -> Vec<String> {
+        let mut labels = Vec::<String>::new();
+        for (component, id) in self.components.iter().zip(state) {
+            for ap in component.aps(id).unwrap() {
+                if !labels.contains(ap) {
+                    labels.push(ap.clone());
+                }
+            }
+        }
+        labels
+    }
+}
+
+/// All ways to pick one element from each list.
+fn cartesian_product<T: Clone>(lists: &[Vec<T>]) -> Vec<Vec<T>> {
+    let mut products = vec![vec![]];
+    for list in lists {
+        products = products
+            .into_iter()
+            .flat_map(|product: Vec<T>| {
+                list.iter().map(move |element| {
+                    let mut product = product.clone();
+                    product.push(element.clone());
+                    product
+                })
+            })
+            .collect();
+    }
+    products
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cartesian_product() {
+        assert_eq!(
+            cartesian_product(&[vec![1, 2], vec![3], vec![4, 5]]),
+            vec![vec![1, 3, 4], vec![1, 3, 5], vec![2, 3, 4], vec![2, 3, 5]]
+        );
+        assert!(cartesian_product(&[vec![1], vec![]]).is_empty());
+    }
+}
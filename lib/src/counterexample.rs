@@ -0,0 +1,118 @@
+use crate::kripke::KripkeNode;
+use crate::KripkeBuilder;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+/// Path of the model, given as state ids, which violates the checked formula. It consists of a
+/// finite prefix followed by a cycle that is repeated forever. Counterexamples of
+/// [`ltlf_model_check`](crate::ltlf_model_check) are finite and have no cycle.
+///
+/// If the path runs into a deadlock, [`deadlock`](Counterexample::deadlock) returns the
+/// deadlocked state. Depending on the [`DeadlockPolicy`](crate::DeadlockPolicy), it is either
+/// the last state of the prefix and the cycle is empty, or it repeats itself as the cycle.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Counterexample<Id = u64> {
+    prefix: Vec<Id>,
+    cycle: Vec<Id>,
+    deadlock: Option<Id>,
+}
+
+impl<Id: Hash + Eq + Clone> Counterexample<Id> {
+    /// Converts an accepting lasso of the product automaton, as returned by the nested DFS, to
+    /// a path of the model. The lasso starts with the state closing the cycle, followed by the
+    /// path from the initial state in reverse.
+    pub(crate) fn from_lasso<T: PartialEq>(
+        lasso: Vec<(KripkeNode, T)>,
+        ks: &KripkeBuilder<Id>,
+    ) -> Self {
+        let mut lasso = lasso;
+        lasso.reverse();
+        let closing = lasso.pop().unwrap();
+        let cycle_start = lasso.iter().position(|state| *state == closing).unwrap();
+        let model_states = |states: &[(KripkeNode, T)]| -> Vec<Option<Id>> {
+            states
+                .iter()
+                .map(|(state, _)| match state {
+                    KripkeNode::State(index) => Some(ks.id_of(*index).clone()),
+                    KripkeNode::Initial | KripkeNode::DeadSink => None,
+                })
+                .collect()
+        };
+        let deadlocks = ks.deadlock_states();
+        Self::from_path(
+            model_states(&lasso[..cycle_start]),
+            model_states(&lasso[cycle_start..]),
+            |id| deadlocks.contains(id),
+        )
+    }
+
+    /// Creates a counterexample from the prefix and cycle of a lasso, in which internal states
+    /// of the model automaton are `None`.
+    pub(crate) fn from_path(
+        prefix: Vec<Option<Id>>,
+        cycle: Vec<Option<Id>>,
+        is_deadlock: impl Fn(&Id) -> bool,
+    ) -> Self {
+        let prefix: Vec<Id> = prefix.into_iter().flatten().collect();
+        let mut cycle: Vec<Id> = cycle.into_iter().flatten().collect();
+
+        let deadlock = if cycle.is_empty() {
+            prefix.last().filter(|state| is_deadlock(state)).cloned()
+        } else if cycle.iter().all(|state| *state == cycle[0]) && is_deadlock(&cycle[0]) {
+            cycle.truncate(1);
+            Some(cycle[0].clone())
+        } else {
+            None
+        };
+
+        Counterexample {
+            prefix,
+            cycle,
+            deadlock,
+        }
+    }
+}
+
+impl<Id> Counterexample<Id> {
+    pub fn prefix(&self) -> &[Id] {
+        &self.prefix
+    }
+
+    pub fn cycle(&self) -> &[Id] {
+        &self.cycle
+    }
+
+    pub fn deadlock(&self) -> Option<&Id> {
+        self.deadlock.as_ref()
+    }
+
+    /// All states of the path, the prefix followed by one pass through the cycle.
+    pub fn states(&self) -> impl Iterator<Item = &Id> + '_ {
+        self.prefix.iter().chain(self.cycle.iter())
+    }
+}
+
+impl<Id: Display> Display for Counterexample<Id> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let join = |states: &[Id]| {
+            states
+                .iter()
+                .map(|state| state.to_string())
+                .collect::<Vec<String>>()
+                .join(" -> ")
+        };
+        write!(f, "{}", join(&self.prefix))?;
+        if !self.cycle.is_empty() {
+            if !self.prefix.is_empty() {
+                write!(f, " -> ")?;
+            }
+            write!(f, "({})^ω", join(&self.cycle))?;
+        }
+        if let Some(state) = &self.deadlock {
+            write!(f, " (deadlock in state {})", state)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::parsing::{self, LTLFormula};
+use crate::ModelCheckingError;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitOr, Not};
+
+/// LTL formula together with the names of its propositions.
+///
+/// Formulas are built from [`ap`] with the methods of this type, the functions [`next`],
+/// [`eventually`] and [`always`], and the operators `&`, `|` and `!`:
+///
+/// ```
+/// use model_checker::{ap, eventually};
+///
+/// let response = ap("req").implies(eventually(ap("grant"))).always();
+/// assert_eq!(response.to_string(), "G (req -> F grant)");
+/// assert_eq!((!ap("a") & ap("b") | ap("c")).to_string(), "!a & b | c");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formula {
+    ltl: LTLFormula,
+    ap_map: HashMap<String, u8>,
+}
+
+/// The proposition called `name`.
+pub fn ap(name: &str) -> Formula {
+    Formula {
+        ltl: LTLFormula::ap(0),
+        ap_map: HashMap::from([(name.to_string(), 0)]),
+    }
+}
+
+/// `X phi`
+pub fn next(phi: Formula) -> Formula {
+    phi.next()
+}
+
+/// `F phi`
+pub fn eventually(phi: Formula) -> Formula {
+    phi.eventually()
+}
+
+/// `G phi`
+pub fn always(phi: Formula) -> Formula {
+    phi.always()
+}
+
+impl Formula {
+    /// Parses `text` in the syntax of [`crate::ltl_model_check`].
+    pub fn parse(text: &str) -> Result<Formula, ModelCheckingError> {
+        let (ltl, ap_map) = parsing::parse(text)?;
+        Ok(Formula { ltl, ap_map })
+    }
+
+    /// Formula whose propositions are named by `ap_map`, which has to name the propositions
+    /// `0..ap_map.len()`.
+    pub fn from_parts(ltl: LTLFormula, ap_map: HashMap<String, u8>) -> Formula {
+        Formula { ltl, ap_map }
+    }
+
+    pub fn ltl(&self) -> &LTLFormula {
+        &self.ltl
+    }
+
+    /// Number of each proposition of [`Formula::ltl`].
+    pub fn ap_map(&self) -> &HashMap<String, u8> {
+        &self.ap_map
+    }
+
+    pub fn into_parts(self) -> (LTLFormula, HashMap<String, u8>) {
+        (self.ltl, self.ap_map)
+    }
+
+    fn map(self, f: impl FnOnce(LTLFormula) -> LTLFormula) -> Formula {
+        Formula {
+            ltl: f(self.ltl),
+            ap_map: self.ap_map,
+        }
+    }
+
+    /// Combines both formulas, renumbering the propositions of `other` that `self` does not
+    /// have. Names whose propositions no longer occur, such as `a` in `b | (a -> a)`, where
+    /// `a -> a` is encoded with the first proposition like `true`, are dropped.
+    fn combine(
+        self,
+        other: Formula,
+        f: impl FnOnce(LTLFormula, LTLFormula) -> LTLFormula,
+    ) -> Formula {
+        let mut ap_map = self.ap_map;
+        let mut numbers = HashMap::<u8, u8>::new();
+        for (name, ap) in other.ap_map {
+            let next = u8::try_from(ap_map.len()).expect("at most 256 propositions");
+            numbers.insert(ap, *ap_map.entry(name).or_insert(next));
+        }
+        let other = other.ltl.map_aps(&|ap| numbers[&ap]);
+        let ltl = f(self.ltl, other);
+        let occurring = ltl.aps();
+        if occurring.len() == ap_map.len() {
+            return Formula { ltl, ap_map };
+        }
+        let numbers: HashMap<u8, u8> = occurring
+            .iter()
+            .enumerate()
+            .map(|(number, ap)| (*ap, number as u8))
+            .collect();
+        ap_map.retain(|_, ap| occurring.contains(ap));
+        for ap in ap_map.values_mut() {
+            *ap = numbers[ap];
+        }
+        Formula {
+            ltl: ltl.map_aps(&|ap| numbers[&ap]),
+            ap_map,
+        }
+    }
+
+    pub fn and(self, other: Formula) -> Formula {
+        self.combine(other, LTLFormula::and)
+    }
+
+    pub fn or(self, other: Formula) -> Formula {
+        self.combine(other, LTLFormula::or)
+    }
+
+    pub fn implies(self, other: Formula) -> Formula {
+        self.combine(other, LTLFormula::implies)
+    }
+
+    pub fn equivalent(self, other: Formula) -> Formula {
+        self.combine(other, LTLFormula::equivalent)
+    }
+
+    pub fn until(self, other: Formula) -> Formula {
+        self.combine(other, |phi1, phi2| LTLFormula::until(phi1, phi2, false))
+    }
+
+    pub fn weak_until(self, other: Formula) -> Formula {
+        self.combine(other, |phi1, phi2| LTLFormula::until(phi1, phi2, true))
+    }
+
+    pub fn release(self, other: Formula) -> Formula {
+        self.combine(other, LTLFormula::release)
+    }
+
+    pub fn next(self) -> Formula {
+        self.map(LTLFormula::next)
+    }
+
+    pub fn weak_next(self) -> Formula {
+        self.map(LTLFormula::weak_next)
+    }
+
+    pub fn eventually(self) -> Formula {
+        self.map(LTLFormula::eventually)
+    }
+
+    pub fn always(self) -> Formula {
+        self.map(LTLFormula::always)
+    }
+}
+
+impl Not for Formula {
+    type Output = Formula;
+
+    fn not(self) -> Formula {
+        self.map(LTLFormula::not)
+    }
+}
+
+impl BitAnd for Formula {
+    type Output = Formula;
+
+    fn bitand(self, other: Formula) -> Formula {
+        self.and(other)
+    }
+}
+
+impl BitOr for Formula {
+    type Output = Formula;
+
+    fn bitor(self, other: Formula) -> Formula {
+        self.or(other)
+    }
+}
+
+impl Display for Formula {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.ltl.to_string_with(&self.ap_map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let formula = ap("req").implies(eventually(ap("grant"))).always();
+        assert_eq!(formula.to_string(), "G (req -> F grant)");
+
+        let formula = (ap("a") | next(ap("b"))).until(!ap("a") & ap("c")) & always(ap("b"));
+        assert_eq!(formula.ap_map().len(), 3);
+        assert_eq!(formula.to_string(), "(a | X b U !a & c) & G b");
+        assert_eq!(
+            Formula::parse(&formula.to_string()).unwrap().to_string(),
+            formula.to_string()
+        );
+        assert_eq!(
+            ap("a")
+                .weak_until(ap("b"))
+                .release(ap("a").equivalent(ap("b")))
+                .to_string(),
+            "a W b R a <-> b"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+use crate::guarded::program_error::{ProgramError, ProgramErrorKind};
+use crate::KripkeBuilder;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+mod lexer;
+mod parser;
+pub mod program_error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Domain {
+    Bool,
+    Range(i64, i64),
+}
+
+impl Domain {
+    fn contains(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Domain::Bool, Value::Bool(_)) => true,
+            (Domain::Range(min, max), Value::Int(i)) => min <= i && i <= max,
+            _ => false,
+        }
+    }
+
+    fn first(&self) -> Value {
+        match self {
+            Domain::Bool => Value::Bool(false),
+            Domain::Range(min, _) => Value::Int(*min),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Variable {
+    name: String,
+    domain: Domain,
+    init: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnaryOperator {
+    Not,
+    Minus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryOperator {
+    And,
+    Or,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Plus,
+    Minus,
+    Times,
+    Divide,
+    Modulo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expression {
+    Constant(Value),
+    Variable(usize),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    fn evaluate(&self, valuation: &[Value]) -> Result<Value, ProgramErrorKind> {
+        Ok(match self {
+            Expression::Constant(value) => *value,
+            Expression::Variable(index) => valuation[*index],
+            Expression::Unary(operator, phi) => match (operator, phi.evaluate(valuation)?) {
+                (UnaryOperator::Not, Value::Bool(b)) => Value::Bool(!b),
+                (UnaryOperator::Minus, Value::Int(i)) => Value::Int(i.wrapping_neg()),
+                _ => return Err(ProgramErrorKind::TypeMismatch),
+            },
+            Expression::Binary(operator, phi1, phi2) => {
+                let value1 = phi1.evaluate(valuation)?;
+                // Short-circuit, so that guards like `x != 0 & 10 / x > 2` are safe.
+                match (operator, value1) {
+                    (BinaryOperator::And, Value::Bool(false)) => return Ok(Value::Bool(false)),
+                    (BinaryOperator::Or, Value::Bool(true)) => return Ok(Value::Bool(true)),
+                    _ => {}
+                }
+                let value2 = phi2.evaluate(valuation)?;
+                match (value1, value2) {
+                    (Value::Bool(b1), Value::Bool(b2)) => match operator {
+                        BinaryOperator::And => Value::Bool(b1 && b2),
+                        BinaryOperator::Or => Value::Bool(b1 || b2),
+                        BinaryOperator::Equal => Value::Bool(b1 == b2),
+                        BinaryOperator::NotEqual => Value::Bool(b1 != b2),
+                        _ => return Err(ProgramErrorKind::TypeMismatch),
+                    },
+                    (Value::Int(i1), Value::Int(i2)) => match operator {
+                        BinaryOperator::Equal => Value::Bool(i1 == i2),
+                        BinaryOperator::NotEqual => Value::Bool(i1 != i2),
+                        BinaryOperator::Less => Value::Bool(i1 < i2),
+                        BinaryOperator::LessEqual => Value::Bool(i1 <= i2),
+                        BinaryOperator::Greater => Value::Bool(i1 > i2),
+                        BinaryOperator::GreaterEqual => Value::Bool(i1 >= i2),
+                        BinaryOperator::Plus => Value::Int(i1.wrapping_add(i2)),
+                        BinaryOperator::Minus => Value::Int(i1.wrapping_sub(i2)),
+                        BinaryOperator::Times => Value::Int(i1.wrapping_mul(i2)),
+                        BinaryOperator::Divide | BinaryOperator::Modulo if i2 == 0 => {
+                            return Err(ProgramErrorKind::DivisionByZero)
+                        }
+                        BinaryOperator::Divide => Value::Int(i1.wrapping_div(i2)),
+                        BinaryOperator::Modulo => Value::Int(i1.wrapping_rem(i2)),
+                        _ => return Err(ProgramErrorKind::TypeMismatch),
+                    },
+                    _ => return Err(ProgramErrorKind::TypeMismatch),
+                }
+            }
+        })
+    }
+
+    fn holds(&self, valuation: &[Value]) -> Result<bool, ProgramErrorKind> {
+        match self.evaluate(valuation)? {
+            Value::Bool(b) => Ok(b),
+            Value::Int(_) => Err(ProgramErrorKind::TypeMismatch),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Command {
+    action: Option<String>,
+    guard: Expression,
+    updates: Vec<(usize, Expression)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Process {
+    name: String,
+    commands: Vec<Command>,
+}
+
+/// A program in the guarded-command modelling language.
+///
+/// A program consists of variable declarations, labels and processes:
+///
+/// ```text
+/// var x: 0..5 init 0;
+/// var ready: bool;
+/// label full = x = 5;
+///
+/// process producer {
+///     [produce] !full -> x := x + 1;
+///     [] full & !ready -> ready := true, x := 0;
+/// }
+/// ```
+///
+/// Variables are either `bool` or integers in a closed range and start in their `init` value,
+/// or in the smallest value of their domain. Names have to be declared before they are used.
+/// In each step, one enabled command of one process is executed; its updates are applied
+/// simultaneously. The optional name in brackets becomes the action of the transition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    variables: Vec<Variable>,
+    labels: Vec<(String, Expression)>,
+    processes: Vec<Process>,
+}
+
+impl Program {
+    pub fn parse(text: &str) -> Result<Program, ProgramError> {
+        let tokens = lexer::lexer(text)?;
+        parser::parser(text, tokens)
+    }
+
+    fn has_action(&self, name: &str) -> bool {
+        self.processes
+            .iter()
+            .flat_map(|process| process.commands.iter())
+            .any(|command| command.action.as_deref() == Some(name))
+    }
+
+    /// Generates the reachable state space of the program.
+    ///
+    /// Every state is labelled with its true boolean variables and labels. Additionally, each
+    /// entry of `propositions` that is not an action name is read as a boolean expression over
+    /// the variables (e.g. `x > 3`) and labels the states it holds in.
+    pub fn generate(&self, propositions: &[String]) -> Result<GeneratedModel, ProgramError> {
+        let mut aps = Vec::<(String, Expression)>::new();
+        for (i, variable) in self.variables.iter().enumerate() {
+            if variable.domain == Domain::Bool {
+                aps.push((variable.name.clone(), Expression::Variable(i)));
+            }
+        }
+        aps.extend(self.labels.iter().cloned());
+        for proposition in propositions {
+            if self.has_action(proposition) || aps.iter().any(|(name, _)| name == proposition) {
+                continue;
+            }
+            let expression = parser::expression_parser(
+                proposition,
+                lexer::lexer(proposition).map_err(|_| {
+                    ProgramError::new(
+                        ProgramErrorKind::UnknownProposition(proposition.clone()),
+                        "",
+                        None,
+                    )
+                })?,
+                self,
+            )
+            .map_err(|_| {
+                ProgramError::new(
+                    ProgramErrorKind::UnknownProposition(proposition.clone()),
+                    "",
+                    None,
+                )
+            })?;
+            aps.push((proposition.clone(), expression));
+        }
+
+        let error = |kind| ProgramError::new(kind, "", None);
+        let initial: Vec<Value> = self.variables.iter().map(|v| v.init).collect();
+        let mut ids = HashMap::<Vec<Value>, u64>::new();
+        let mut valuations = Vec::<Vec<Value>>::new();
+        let mut queue = VecDeque::<u64>::new();
+        let mut kripke_builder = KripkeBuilder::new();
+
+        ids.insert(initial.clone(), 0);
+        valuations.push(initial);
+        queue.push_back(0);
+
+        while let Some(id) = queue.pop_front() {
+            let valuation = valuations[id as usize].clone();
+
+            let mut labels = Vec::<String>::new();
+            for (name, expression) in aps.iter() {
+                if expression.holds(&valuation).map_err(error)? {
+                    labels.push(name.clone());
+                }
+            }
+            kripke_builder.add_state(labels, id, id == 0);
+
+            for command in self.processes.iter().flat_map(|p| p.commands.iter()) {
+                if !command.guard.holds(&valuation).map_err(error)? {
+                    continue;
+                }
+                let mut successor = valuation.clone();
+                for (variable, expression) in command.updates.iter() {
+                    let value = expression.evaluate(&valuation).map_err(error)?;
+                    if !self.variables[*variable].domain.contains(&value) {
+                        return Err(error(ProgramErrorKind::ValueOutOfDomain(
+                            self.variables[*variable].name.clone(),
+                        )));
+                    }
+                    successor[*variable] = value;
+                }
+                let successor_id = match ids.get(&successor) {
+                    Some(successor_id) => *successor_id,
+                    None => {
+                        let successor_id = valuations.len() as u64;
+                        ids.insert(successor.clone(), successor_id);
+                        valuations.push(successor);
+                        queue.push_back(successor_id);
+                        successor_id
+                    }
+                };
+                match &command.action {
+                    Some(action) => {
+                        kripke_builder.add_labelled_transition(id, successor_id, action.clone())
+                    }
+                    None => kripke_builder.add_transition(id, successor_id),
+                };
+            }
+        }
+
+        Ok(GeneratedModel {
+            kripke_builder,
+            variable_names: self.variables.iter().map(|v| v.name.clone()).collect(),
+            valuations,
+        })
+    }
+}
+
+/// Explicit state space of a [`Program`]. State ids are the indices of the valuations.
+#[derive(Debug, Clone)]
+pub struct GeneratedModel {
+    kripke_builder: KripkeBuilder,
+    variable_names: Vec<String>,
+    valuations: Vec<Vec<Value>>,
+}
+
+impl GeneratedModel {
+    pub fn kripke_builder(&self) -> &KripkeBuilder {
+        &self.kripke_builder
+    }
+
+    pub fn into_kripke_builder(self) -> KripkeBuilder {
+        self.kripke_builder
+    }
+
+    pub fn amount_states(&self) -> usize {
+        self.valuations.len()
+    }
+
+    pub fn valuation(&self, state_id: u64) -> Option<Vec<(&str, Value)>> {
+        self.valuations.get(state_id as usize).map(|valuation| {
+            self.variable_names
+                .iter()
+                .map(|name| name.as_str())
+                .zip(valuation.iter().copied())
+                .collect()
+        })
+    }
+
+    /// Formats the variable values of a state as `x = 1, ready = false`.
+    pub fn format_state(&self, state_id: u64) -> Option<String> {
+        self.valuation(state_id).map(|valuation| {
+            valuation
+                .iter()
+                .map(|(name, value)| format!("{} = {}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: &str = "
+        var x: 0..3 init 1;
+        var up: bool init true;
+        label top = x = 3;
+        process counter {
+            [inc] up & !top -> x := x + 1;
+            [turn] top | x = 0 -> up := !up;
+            [dec] !up & x > 0 -> x := x - 1;
+        }
+    ";
+
+    #[test]
+    fn test_generation() {
+        let program = Program::parse(COUNTER).unwrap();
+        let model = program.generate(&["x >= 2".to_string()]).unwrap();
+        assert_eq!(model.amount_states(), 8);
+        assert_eq!(model.format_state(0), Some("x = 1, up = true".to_string()));
+        assert_eq!(
+            model.valuation(1),
+            Some(vec![("x", Value::Int(2)), ("up", Value::Bool(true))])
+        );
+        assert_eq!(model.valuation(8), None);
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let program = Program::parse("var x: 0..1; process p { [] true -> x := x + 1; }").unwrap();
+        assert_eq!(
+            program.generate(&[]).unwrap_err().kind(),
+            &ProgramErrorKind::ValueOutOfDomain("x".to_string())
+        );
+        let program = Program::parse("var x: 0..1; process p { [] x + 1 -> skip; }").unwrap();
+        assert_eq!(
+            program.generate(&[]).unwrap_err().kind(),
+            &ProgramErrorKind::TypeMismatch
+        );
+        let program = Program::parse("var x: 0..1;").unwrap();
+        assert_eq!(
+            program.generate(&["y".to_string()]).unwrap_err().kind(),
+            &ProgramErrorKind::UnknownProposition("y".to_string())
+        );
+        // Negation overflows like the binary operators instead of panicking.
+        let program = Program::parse(
+            "var x: 0..1;
+            process p {
+                [] x = 0 -> x := 1;
+                [] x = 1 & (-(-9223372036854775807 - 1) > 0) -> x := 0;
+            }",
+        )
+        .unwrap();
+        assert_eq!(program.generate(&[]).unwrap().amount_states(), 2);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::guarded::program_error::{ProgramError, ProgramErrorKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Identifier(String),
+    Integer(i64),
+    Var,
+    Bool,
+    Init,
+    Label,
+    Process,
+    True,
+    False,
+    Skip,
+    OpenBracket,
+    CloseBracket,
+    OpenBrace,
+    CloseBrace,
+    OpenParenthesis,
+    CloseParenthesis,
+    Semicolon,
+    Colon,
+    Comma,
+    Range,
+    Assign,
+    Arrow,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Plus,
+    Minus,
+    Times,
+    Divide,
+    Modulo,
+    And,
+    Or,
+    Not,
+}
+
+/// Splits a program into tokens, each paired with the character offset it starts at.
+pub fn lexer(text: &str) -> Result<Vec<(Token, usize)>, ProgramError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::<(Token, usize)>::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                name.push(chars[i]);
+                i += 1;
+            }
+            let token = match name.as_str() {
+                "var" => Token::Var,
+                "bool" => Token::Bool,
+                "init" => Token::Init,
+                "label" => Token::Label,
+                "process" => Token::Process,
+                "true" => Token::True,
+                "false" => Token::False,
+                "skip" => Token::Skip,
+                _ => Token::Identifier(name),
+            };
+            tokens.push((token, start));
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let mut value: i64 = 0;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                value = value
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(chars[i].to_digit(10).unwrap() as i64))
+                    .ok_or_else(|| {
+                        ProgramError::new(ProgramErrorKind::UnexpectedToken, text, Some(start))
+                    })?;
+                i += 1;
+            }
+            tokens.push((Token::Integer(value), start));
+            continue;
+        }
+
+        let (token, length) = match (c, next) {
+            ('.', Some('.')) => (Token::Range, 2),
+            (':', Some('=')) => (Token::Assign, 2),
+            ('-', Some('>')) => (Token::Arrow, 2),
+            ('=', Some('=')) => (Token::Equal, 2),
+            ('!', Some('=')) => (Token::NotEqual, 2),
+            ('<', Some('=')) => (Token::LessEqual, 2),
+            ('>', Some('=')) => (Token::GreaterEqual, 2),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('[', _) => (Token::OpenBracket, 1),
+            (']', _) => (Token::CloseBracket, 1),
+            ('{', _) => (Token::OpenBrace, 1),
+            ('}', _) => (Token::CloseBrace, 1),
+            ('(', _) => (Token::OpenParenthesis, 1),
+            (')', _) => (Token::CloseParenthesis, 1),
+            (';', _) => (Token::Semicolon, 1),
+            (':', _) => (Token::Colon, 1),
+            (',', _) => (Token::Comma, 1),
+            ('=', _) => (Token::Equal, 1),
+            ('<', _) => (Token::Less, 1),
+            ('>', _) => (Token::Greater, 1),
+            ('+', _) => (Token::Plus, 1),
+            ('-', _) => (Token::Minus, 1),
+            ('*', _) => (Token::Times, 1),
+            ('/', _) => (Token::Divide, 1),
+            ('%', _) => (Token::Modulo, 1),
+            ('&', _) => (Token::And, 1),
+            ('|', _) => (Token::Or, 1),
+            ('!', _) => (Token::Not, 1),
+            _ => {
+                return Err(ProgramError::new(
+                    ProgramErrorKind::UnexpectedCharacter,
+                    text,
+                    Some(start),
+                ))
+            }
+        };
+        tokens.push((token, start));
+        i += length;
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let tokens: Vec<Token> = lexer("var x: 0..3 init 1; // comment\n[go] x >= 2 -> x := x-1;")
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Var,
+                Token::Identifier("x".to_string()),
+                Token::Colon,
+                Token::Integer(0),
+                Token::Range,
+                Token::Integer(3),
+                Token::Init,
+                Token::Integer(1),
+                Token::Semicolon,
+                Token::OpenBracket,
+                Token::Identifier("go".to_string()),
+                Token::CloseBracket,
+                Token::Identifier("x".to_string()),
+                Token::GreaterEqual,
+                Token::Integer(2),
+                Token::Arrow,
+                Token::Identifier("x".to_string()),
+                Token::Assign,
+                Token::Identifier("x".to_string()),
+                Token::Minus,
+                Token::Integer(1),
+                Token::Semicolon,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_chars() {
+        let error = lexer("var x: bool;\nx ? 1").unwrap_err();
+        assert_eq!(error.kind(), &ProgramErrorKind::UnexpectedCharacter);
+        assert_eq!(error.position(), Some((2, 3)));
+    }
+}
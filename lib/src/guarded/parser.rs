@@ -0,0 +1,451 @@
+use crate::guarded::lexer::Token;
+use crate::guarded::program_error::{ProgramError, ProgramErrorKind};
+use crate::guarded::{
+    BinaryOperator, Command, Domain, Expression, Process, Program, UnaryOperator, Value, Variable,
+};
+
+struct Parser<'a> {
+    text: &'a str,
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    variables: Vec<Variable>,
+    labels: Vec<(String, Expression)>,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: ProgramErrorKind) -> ProgramError {
+        match self.tokens.get(self.position) {
+            Some((_, at)) => ProgramError::new(kind, self.text, Some(*at)),
+            None => ProgramError::new(
+                ProgramErrorKind::UnexpectedEnd,
+                self.text,
+                Some(self.text.chars().count()),
+            ),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, ProgramError> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error(ProgramErrorKind::UnexpectedEnd))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), ProgramError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(ProgramErrorKind::UnexpectedToken))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, ProgramError> {
+        match self.peek() {
+            Some(Token::Identifier(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Ok(name)
+            }
+            _ => Err(self.error(ProgramErrorKind::UnexpectedToken)),
+        }
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.variables.iter().any(|v| v.name == name) || self.labels.iter().any(|l| l.0 == name)
+    }
+
+    fn integer(&mut self) -> Result<i64, ProgramError> {
+        let start = self.position;
+        let negative = self.eat(&Token::Minus);
+        match self.next()? {
+            Token::Integer(i) if negative => Ok(-i),
+            Token::Integer(i) => Ok(i),
+            _ => {
+                self.position = start;
+                Err(self.error(ProgramErrorKind::UnexpectedToken))
+            }
+        }
+    }
+
+    fn variable_declaration(&mut self) -> Result<(), ProgramError> {
+        self.expect(&Token::Var)?;
+        let start = self.position;
+        let name = self.identifier()?;
+        if self.is_declared(&name) {
+            self.position = start;
+            return Err(self.error(ProgramErrorKind::DuplicateName(name)));
+        }
+        self.expect(&Token::Colon)?;
+        let domain = if self.eat(&Token::Bool) {
+            Domain::Bool
+        } else {
+            let min = self.integer()?;
+            self.expect(&Token::Range)?;
+            let max = self.integer()?;
+            if max < min {
+                self.position = start;
+                return Err(self.error(ProgramErrorKind::EmptyDomain(name)));
+            }
+            Domain::Range(min, max)
+        };
+        let init = if self.eat(&Token::Init) {
+            let init_position = self.position;
+            let value = match self.peek() {
+                Some(Token::True) => {
+                    self.position += 1;
+                    Value::Bool(true)
+                }
+                Some(Token::False) => {
+                    self.position += 1;
+                    Value::Bool(false)
+                }
+                _ => Value::Int(self.integer()?),
+            };
+            if !domain.contains(&value) {
+                self.position = init_position;
+                return Err(self.error(ProgramErrorKind::InitialValueOutOfDomain(name)));
+            }
+            value
+        } else {
+            domain.first()
+        };
+        self.expect(&Token::Semicolon)?;
+        self.variables.push(Variable { name, domain, init });
+        Ok(())
+    }
+
+    fn label_declaration(&mut self) -> Result<(), ProgramError> {
+        self.expect(&Token::Label)?;
+        let start = self.position;
+        let name = self.identifier()?;
+        if self.is_declared(&name) {
+            self.position = start;
+            return Err(self.error(ProgramErrorKind::DuplicateName(name)));
+        }
+        self.expect(&Token::Equal)?;
+        let expression = self.expression()?;
+        self.expect(&Token::Semicolon)?;
+        self.labels.push((name, expression));
+        Ok(())
+    }
+
+    fn command(&mut self) -> Result<Command, ProgramError> {
+        self.expect(&Token::OpenBracket)?;
+        let action = if self.eat(&Token::CloseBracket) {
+            None
+        } else {
+            let action = self.identifier()?;
+            self.expect(&Token::CloseBracket)?;
+            Some(action)
+        };
+        let guard = self.expression()?;
+        self.expect(&Token::Arrow)?;
+        let mut updates = Vec::<(usize, Expression)>::new();
+        if !self.eat(&Token::Skip) {
+            loop {
+                let start = self.position;
+                let name = self.identifier()?;
+                let Some(variable) = self.variables.iter().position(|v| v.name == name) else {
+                    self.position = start;
+                    return Err(self.error(ProgramErrorKind::UnknownVariable(name)));
+                };
+                if updates.iter().any(|(v, _)| *v == variable) {
+                    self.position = start;
+                    return Err(self.error(ProgramErrorKind::DuplicateName(name)));
+                }
+                self.expect(&Token::Assign)?;
+                updates.push((variable, self.expression()?));
+                if !self.eat(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        self.expect(&Token::Semicolon)?;
+        Ok(Command {
+            action,
+            guard,
+            updates,
+        })
+    }
+
+    fn process(&mut self, processes: &[Process]) -> Result<Process, ProgramError> {
+        self.expect(&Token::Process)?;
+        let start = self.position;
+        let name = self.identifier()?;
+        if processes.iter().any(|p| p.name == name) {
+            self.position = start;
+            return Err(self.error(ProgramErrorKind::DuplicateName(name)));
+        }
+        self.expect(&Token::OpenBrace)?;
+        let mut commands = Vec::<Command>::new();
+        while !self.eat(&Token::CloseBrace) {
+            match self.peek() {
+                Some(Token::Var) => self.variable_declaration()?,
+                Some(Token::Label) => self.label_declaration()?,
+                _ => commands.push(self.command()?),
+            }
+        }
+        Ok(Process { name, commands })
+    }
+
+    fn expression(&mut self) -> Result<Expression, ProgramError> {
+        let mut phi = self.conjunction()?;
+        while self.eat(&Token::Or) {
+            phi = Expression::Binary(
+                BinaryOperator::Or,
+                Box::new(phi),
+                Box::new(self.conjunction()?),
+            );
+        }
+        Ok(phi)
+    }
+
+    fn conjunction(&mut self) -> Result<Expression, ProgramError> {
+        let mut phi = self.negation()?;
+        while self.eat(&Token::And) {
+            phi = Expression::Binary(
+                BinaryOperator::And,
+                Box::new(phi),
+                Box::new(self.negation()?),
+            );
+        }
+        Ok(phi)
+    }
+
+    fn negation(&mut self) -> Result<Expression, ProgramError> {
+        if self.eat(&Token::Not) {
+            Ok(Expression::Unary(
+                UnaryOperator::Not,
+                Box::new(self.negation()?),
+            ))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expression, ProgramError> {
+        let phi = self.sum()?;
+        let operator = match self.peek() {
+            Some(Token::Equal) => BinaryOperator::Equal,
+            Some(Token::NotEqual) => BinaryOperator::NotEqual,
+            Some(Token::Less) => BinaryOperator::Less,
+            Some(Token::LessEqual) => BinaryOperator::LessEqual,
+            Some(Token::Greater) => BinaryOperator::Greater,
+            Some(Token::GreaterEqual) => BinaryOperator::GreaterEqual,
+            _ => return Ok(phi),
+        };
+        self.position += 1;
+        Ok(Expression::Binary(
+            operator,
+            Box::new(phi),
+            Box::new(self.sum()?),
+        ))
+    }
+
+    fn sum(&mut self) -> Result<Expression, ProgramError> {
+        let mut phi = self.product()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Plus) => BinaryOperator::Plus,
+                Some(Token::Minus) => BinaryOperator::Minus,
+                _ => return Ok(phi),
+            };
+            self.position += 1;
+            phi = Expression::Binary(operator, Box::new(phi), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expression, ProgramError> {
+        let mut phi = self.unary()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Times) => BinaryOperator::Times,
+                Some(Token::Divide) => BinaryOperator::Divide,
+                Some(Token::Modulo) => BinaryOperator::Modulo,
+                _ => return Ok(phi),
+            };
+            self.position += 1;
+            phi = Expression::Binary(operator, Box::new(phi), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression, ProgramError> {
+        if self.eat(&Token::Minus) {
+            Ok(Expression::Unary(
+                UnaryOperator::Minus,
+                Box::new(self.unary()?),
+            ))
+        } else if self.eat(&Token::Not) {
+            Ok(Expression::Unary(
+                UnaryOperator::Not,
+                Box::new(self.unary()?),
+            ))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, ProgramError> {
+        let start = self.position;
+        Ok(match self.next()? {
+            Token::Integer(i) => Expression::Constant(Value::Int(i)),
+            Token::True => Expression::Constant(Value::Bool(true)),
+            Token::False => Expression::Constant(Value::Bool(false)),
+            Token::OpenParenthesis => {
+                let phi = self.expression()?;
+                self.expect(&Token::CloseParenthesis)?;
+                phi
+            }
+            Token::Identifier(name) => {
+                if let Some(variable) = self.variables.iter().position(|v| v.name == name) {
+                    Expression::Variable(variable)
+                } else if let Some((_, label)) = self.labels.iter().find(|l| l.0 == name) {
+                    label.clone()
+                } else {
+                    self.position = start;
+                    return Err(self.error(ProgramErrorKind::UnknownVariable(name)));
+                }
+            }
+            _ => {
+                self.position = start;
+                return Err(self.error(ProgramErrorKind::UnexpectedToken));
+            }
+        })
+    }
+}
+
+pub fn parser(text: &str, tokens: Vec<(Token, usize)>) -> Result<Program, ProgramError> {
+    let mut parser = Parser {
+        text,
+        tokens,
+        position: 0,
+        variables: vec![],
+        labels: vec![],
+    };
+    let mut processes = Vec::<Process>::new();
+
+    while let Some(token) = parser.peek() {
+        match token {
+            Token::Var => parser.variable_declaration()?,
+            Token::Label => parser.label_declaration()?,
+            Token::Process => {
+                let process = parser.process(&processes)?;
+                processes.push(process);
+            }
+            _ => return Err(parser.error(ProgramErrorKind::UnexpectedToken)),
+        }
+    }
+
+    Ok(Program {
+        variables: parser.variables,
+        labels: parser.labels,
+        processes,
+    })
+}
+
+/// Parses a single expression over the variables and labels of `program`.
+pub fn expression_parser(
+    text: &str,
+    tokens: Vec<(Token, usize)>,
+    program: &Program,
+) -> Result<Expression, ProgramError> {
+    let mut parser = Parser {
+        text,
+        tokens,
+        position: 0,
+        variables: program.variables.clone(),
+        labels: program.labels.clone(),
+    };
+    let expression = parser.expression()?;
+    if parser.peek().is_some() {
+        return Err(parser.error(ProgramErrorKind::UnexpectedToken));
+    }
+    Ok(expression)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guarded::lexer::lexer;
+
+    fn parse(text: &str) -> Result<Program, ProgramError> {
+        parser(text, lexer(text)?)
+    }
+
+    #[test]
+    fn test_basic_parsing() {
+        let program = parse(
+            "var x: -1..2 init 0; label pos = x > 0;
+            process p { var b: bool; [tick] pos | b -> x := x - 1, b := !b; [] x = -1 -> skip; }",
+        )
+        .unwrap();
+        assert_eq!(program.variables.len(), 2);
+        assert_eq!(program.variables[0].domain, Domain::Range(-1, 2));
+        assert_eq!(program.variables[0].init, Value::Int(0));
+        assert_eq!(program.variables[1].init, Value::Bool(false));
+        assert_eq!(program.processes[0].commands.len(), 2);
+        assert_eq!(
+            program.processes[0].commands[0].action,
+            Some("tick".to_string())
+        );
+        assert_eq!(
+            program.processes[0].commands[1].guard,
+            Expression::Binary(
+                BinaryOperator::Equal,
+                Box::new(Expression::Variable(0)),
+                Box::new(Expression::Unary(
+                    UnaryOperator::Minus,
+                    Box::new(Expression::Constant(Value::Int(1)))
+                ))
+            )
+        );
+    }
+
+    #[test]
+    fn test_proper_errors() {
+        let kind = |text: &str| parse(text).unwrap_err().kind().clone();
+        assert_eq!(
+            kind("var x: bool; var x: 0..1;"),
+            ProgramErrorKind::DuplicateName("x".to_string())
+        );
+        assert_eq!(
+            kind("var x: 2..1;"),
+            ProgramErrorKind::EmptyDomain("x".to_string())
+        );
+        assert_eq!(
+            kind("var x: 0..1 init 2;"),
+            ProgramErrorKind::InitialValueOutOfDomain("x".to_string())
+        );
+        assert_eq!(
+            kind("process p { [] y -> skip; }"),
+            ProgramErrorKind::UnknownVariable("y".to_string())
+        );
+        assert_eq!(
+            kind("var x: bool; process p { [] x skip; }"),
+            ProgramErrorKind::UnexpectedToken
+        );
+        assert_eq!(kind("var x: bool"), ProgramErrorKind::UnexpectedEnd);
+        assert_eq!(
+            parse("var x: bool;\nprocess p { [] x -> x := 1 +; }")
+                .unwrap_err()
+                .position(),
+            Some((2, 29))
+        );
+        assert_eq!(parse("var x: -y..1;").unwrap_err().position(), Some((1, 8)));
+    }
+}
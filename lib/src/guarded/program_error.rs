@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProgramErrorKind {
+    UnexpectedCharacter,
+    UnexpectedToken,
+    UnexpectedEnd,
+    UnknownVariable(String),
+    DuplicateName(String),
+    EmptyDomain(String),
+    InitialValueOutOfDomain(String),
+    ValueOutOfDomain(String),
+    TypeMismatch,
+    DivisionByZero,
+    UnknownProposition(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProgramError {
+    kind: ProgramErrorKind,
+    line: usize,
+    column: usize,
+}
+
+impl ProgramError {
+    /// Creates an error for the character offset `at` in `text`, if the error can be located.
+    pub fn new(kind: ProgramErrorKind, text: &str, at: Option<usize>) -> ProgramError {
+        let (line, column) = match at {
+            Some(at) => {
+                let before: Vec<char> = text.chars().take(at).collect();
+                let line = before.iter().filter(|c| **c == '\n').count() + 1;
+                let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+                (line, column)
+            }
+            None => (0, 0),
+        };
+        ProgramError { kind, line, column }
+    }
+
+    pub fn kind(&self) -> &ProgramErrorKind {
+        &self.kind
+    }
+
+    /// Line and column (both starting at 1) of the error, if it refers to a source position.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        if self.line == 0 {
+            None
+        } else {
+            Some((self.line, self.column))
+        }
+    }
+}
+
+impl Error for ProgramError {}
+
+impl Display for ProgramError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            ProgramErrorKind::UnexpectedCharacter => write!(f, "Unexpected character")?,
+            ProgramErrorKind::UnexpectedToken => write!(f, "Unexpected token")?,
+            ProgramErrorKind::UnexpectedEnd => write!(f, "Unexpected end of program")?,
+            ProgramErrorKind::UnknownVariable(name) => write!(f, "Unknown variable '{}'", name)?,
+            ProgramErrorKind::DuplicateName(name) => {
+                write!(f, "'{}' is declared more than once", name)?
+            }
+            ProgramErrorKind::EmptyDomain(name) => write!(f, "Variable '{}' has no values", name)?,
+            ProgramErrorKind::InitialValueOutOfDomain(name) => {
+                write!(f, "Initial value of '{}' is outside of its domain", name)?
+            }
+            ProgramErrorKind::ValueOutOfDomain(name) => write!(
+                f,
+                "Update assigns a value outside of the domain of '{}'",
+                name
+            )?,
+            ProgramErrorKind::TypeMismatch => write!(f, "Type mismatch")?,
+            ProgramErrorKind::DivisionByZero => write!(f, "Division by zero")?,
+            ProgramErrorKind::UnknownProposition(name) => write!(
+                f,
+                "'{}' is neither a label, an action nor an expression over the variables",
+                name
+            )?,
+        }
+        if let Some((line, column)) = self.position() {
+            write!(f, " at line {}, column {}", line, column)?;
+        }
+        Ok(())
+    }
+}
@@ -2,136 +2,588 @@ use crate::buechi::transitions::Transitions;
 use crate::buechi::Büchi;
 use crate::{ModelCheckingError, ModelCheckingErrorKind};
 use bit_vec::BitVec;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
 
-#[derive(Clone)]
-struct KripkeState {
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct KripkeState<Id> {
     aps: Vec<String>,
-    id: u64,
+    id: Id,
     start: bool,
+    /// Whether finite paths may end in this state, see [`KripkeBuilder::set_final`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    final_state: bool,
+    /// Outgoing transitions as target and action, without duplicates.
+    successors: Vec<(Id, Option<String>)>,
 }
 
-#[derive(Clone)]
-pub struct KripkeBuilder {
-    states: HashMap<u64, KripkeState>,
-    transitions: Vec<(u64, u64)>,
+/// How states without a successor are treated when checking a formula.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeadlockPolicy {
+    /// Deadlocks make the model invalid.
+    Error,
+    /// Deadlocked states repeat themselves forever.
+    Stutter,
+    /// Deadlocked states move to an internal sink state in which no proposition holds.
+    #[default]
+    DeadSink,
+    /// Paths ending in a deadlock are finite and checked with LTLf semantics, i.e. `X a` is
+    /// violated in the last state and `G a` only requires `a` up to the last state.
+    Finite,
 }
 
-impl Default for KripkeBuilder {
+/// State of the automaton created from a model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KripkeNode {
+    /// Initial state, with transitions into all start states.
+    Initial,
+    /// Sink without any propositions, which deadlocked states move to.
+    DeadSink,
+    /// State of the model, given by its index in the order in which states were added.
+    State(usize),
+}
+
+/// Kripke structure whose states are identified by any hashable `Id`, e.g. numbers, names or
+/// tuples. States keep the order in which they were added.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "SerializedKripkeBuilder<Id>",
+        into = "SerializedKripkeBuilder<Id>",
+        bound(
+            serialize = "Id: serde::Serialize + Clone",
+            deserialize = "Id: serde::Deserialize<'de> + Hash + Eq + Clone"
+        )
+    )
+)]
+pub struct KripkeBuilder<Id = u64> {
+    states: Vec<KripkeState<Id>>,
+    indices: HashMap<Id, usize>,
+    /// Transitions whose source state does not exist (yet).
+    pending_transitions: Vec<(Id, Id, Option<String>)>,
+    duplicate_ids: Vec<Id>,
+    deadlock_policy: DeadlockPolicy,
+}
+
+impl<Id: PartialEq> PartialEq for KripkeBuilder<Id> {
+    fn eq(&self, other: &Self) -> bool {
+        // The index of the states follows from the states.
+        self.states == other.states
+            && self.pending_transitions == other.pending_transitions
+            && self.duplicate_ids == other.duplicate_ids
+            && self.deadlock_policy == other.deadlock_policy
+    }
+}
+
+/// [`KripkeBuilder`] without the index of its states, which is rebuilt on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedKripkeBuilder<Id> {
+    states: Vec<KripkeState<Id>>,
+    pending_transitions: Vec<(Id, Id, Option<String>)>,
+    duplicate_ids: Vec<Id>,
+    deadlock_policy: DeadlockPolicy,
+}
+
+#[cfg(feature = "serde")]
+impl<Id> From<KripkeBuilder<Id>> for SerializedKripkeBuilder<Id> {
+    fn from(ks: KripkeBuilder<Id>) -> Self {
+        SerializedKripkeBuilder {
+            states: ks.states,
+            pending_transitions: ks.pending_transitions,
+            duplicate_ids: ks.duplicate_ids,
+            deadlock_policy: ks.deadlock_policy,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Id: Hash + Eq + Clone> From<SerializedKripkeBuilder<Id>> for KripkeBuilder<Id> {
+    fn from(serialized: SerializedKripkeBuilder<Id>) -> Self {
+        let mut ks = KripkeBuilder {
+            states: serialized.states,
+            indices: HashMap::new(),
+            pending_transitions: serialized.pending_transitions,
+            duplicate_ids: serialized.duplicate_ids,
+            deadlock_policy: serialized.deadlock_policy,
+        };
+        ks.rebuild_indices();
+        ks
+    }
+}
+
+impl<Id: Hash + Eq + Clone> Default for KripkeBuilder<Id> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl KripkeBuilder {
-    pub fn new() -> KripkeBuilder {
+impl<Id: Hash + Eq + Clone> KripkeBuilder<Id> {
+    pub fn new() -> KripkeBuilder<Id> {
         KripkeBuilder {
-            states: HashMap::new(),
-            transitions: vec![],
+            states: vec![],
+            indices: HashMap::new(),
+            pending_transitions: vec![],
+            duplicate_ids: vec![],
+            deadlock_policy: DeadlockPolicy::default(),
         }
     }
 
-    pub fn add_state(&mut self, aps: Vec<String>, id: u64, start: bool) {
-        self.states.insert(id, KripkeState { id, aps, start });
+    pub fn set_deadlock_policy(&mut self, policy: DeadlockPolicy) {
+        self.deadlock_policy = policy;
     }
 
-    pub fn add_transition(&mut self, state_id_1: u64, state_id_2: u64) {
-        self.transitions.push((state_id_1, state_id_2));
+    pub fn deadlock_policy(&self) -> DeadlockPolicy {
+        self.deadlock_policy
     }
 
-    fn get_symbol_from_string_aps_with_ap_map(
-        string_aps: &Vec<String>,
-        ap_map: &HashMap<String, u8>,
-    ) -> u64 {
-        let mut symbol = 0;
-        for string_ap in string_aps {
-            if let Some(ap) = ap_map.get(string_ap) {
-                symbol |= 1 << ap;
+    fn rebuild_indices(&mut self) {
+        self.indices = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(index, state)| (state.id.clone(), index))
+            .collect();
+    }
+
+    fn state(&self, id: &Id) -> Option<&KripkeState<Id>> {
+        self.indices.get(id).map(|index| &self.states[*index])
+    }
+
+    fn state_mut(&mut self, id: &Id) -> Option<&mut KripkeState<Id>> {
+        self.indices.get(id).map(|index| &mut self.states[*index])
+    }
+
+    /// Adds a state. Adding a second state with the same id replaces its propositions and
+    /// whether it is a start state, but makes the model invalid.
+    pub fn add_state(&mut self, aps: Vec<String>, id: Id, start: bool) {
+        if let Some(state) = self.state_mut(&id) {
+            state.aps = aps;
+            state.start = start;
+            self.duplicate_ids.push(id);
+            return;
+        }
+
+        let mut successors = vec![];
+        self.pending_transitions.retain(|(from, to, action)| {
+            if *from == id {
+                successors.push((to.clone(), action.clone()));
+                false
+            } else {
+                true
+            }
+        });
+        self.indices.insert(id.clone(), self.states.len());
+        self.states.push(KripkeState {
+            aps,
+            id,
+            start,
+            final_state: false,
+            successors,
+        });
+    }
+
+    /// Removes a state together with all transitions from and to it. Returns whether the state
+    /// existed.
+    pub fn remove_state(&mut self, id: &Id) -> bool {
+        let Some(index) = self.indices.get(id).copied() else {
+            return false;
+        };
+        self.states.remove(index);
+        self.rebuild_indices();
+        for state in &mut self.states {
+            state.successors.retain(|(to, _)| to != id);
+        }
+        self.pending_transitions.retain(|(_, to, _)| to != id);
+        self.duplicate_ids.retain(|duplicate| duplicate != id);
+        true
+    }
+
+    /// Replaces the atomic propositions of a state. Returns whether the state exists.
+    pub fn set_aps(&mut self, id: &Id, aps: Vec<String>) -> bool {
+        self.state_mut(id).map(|state| state.aps = aps).is_some()
+    }
+
+    /// Sets whether a state is a start state. Returns whether the state exists.
+    pub fn set_start(&mut self, id: &Id, start: bool) -> bool {
+        self.state_mut(id)
+            .map(|state| state.start = start)
+            .is_some()
+    }
+
+    /// Sets whether finite paths may end in a state when checking with LTLf semantics, see
+    /// [`ltlf_model_check`](crate::ltlf_model_check). Returns whether the state exists.
+    pub fn set_final(&mut self, id: &Id, final_state: bool) -> bool {
+        self.state_mut(id)
+            .map(|state| state.final_state = final_state)
+            .is_some()
+    }
+
+    pub fn contains_state(&self, id: &Id) -> bool {
+        self.indices.contains_key(id)
+    }
+
+    pub fn amount_states(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn aps(&self, id: &Id) -> Option<&[String]> {
+        self.state(id).map(|state| state.aps.as_slice())
+    }
+
+    pub fn is_start(&self, id: &Id) -> Option<bool> {
+        self.state(id).map(|state| state.start)
+    }
+
+    /// Whether finite paths may end in a state, because it is marked as final or has no
+    /// successor.
+    pub fn is_final(&self, id: &Id) -> Option<bool> {
+        self.state(id)
+            .map(|state| state.final_state || state.successors.is_empty())
+    }
+
+    /// All states as id, atomic propositions and whether they are start states, in the order
+    /// they were added.
+    pub fn states(&self) -> impl Iterator<Item = (&Id, &[String], bool)> + '_ {
+        self.states
+            .iter()
+            .map(|state| (&state.id, state.aps.as_slice(), state.start))
+    }
+
+    /// Adds a transition. Returns `false` if the same transition already exists.
+    pub fn add_transition(&mut self, state_id_1: Id, state_id_2: Id) -> bool {
+        self.insert_transition(state_id_1, state_id_2, None)
+    }
+
+    /// Adds a transition that is labelled with an action. Returns `false` if the same
+    /// transition already exists.
+    ///
+    /// In formulas, an action can be used like an atomic proposition. It holds in the state
+    /// that is entered through a transition carrying that action.
+    pub fn add_labelled_transition(
+        &mut self,
+        state_id_1: Id,
+        state_id_2: Id,
+        action: String,
+    ) -> bool {
+        self.insert_transition(state_id_1, state_id_2, Some(action))
+    }
+
+    fn insert_transition(&mut self, from: Id, to: Id, action: Option<String>) -> bool {
+        if let Some(index) = self.indices.get(&from) {
+            let successors = &mut self.states[*index].successors;
+            if successors.contains(&(to.clone(), action.clone())) {
+                return false;
             }
+            successors.push((to, action));
+        } else {
+            let transition = (from, to, action);
+            if self.pending_transitions.contains(&transition) {
+                return false;
+            }
+            self.pending_transitions.push(transition);
+        }
+        true
+    }
+
+    /// Removes the unlabelled transition between two states. Returns whether it existed.
+    pub fn remove_transition(&mut self, state_id_1: &Id, state_id_2: &Id) -> bool {
+        self.delete_transition(state_id_1, state_id_2, None)
+    }
+
+    /// Removes the transition between two states labelled with `action`. Returns whether it
+    /// existed.
+    pub fn remove_labelled_transition(
+        &mut self,
+        state_id_1: &Id,
+        state_id_2: &Id,
+        action: &str,
+    ) -> bool {
+        self.delete_transition(state_id_1, state_id_2, Some(action))
+    }
+
+    fn delete_transition(&mut self, from: &Id, to: &Id, action: Option<&str>) -> bool {
+        let matches =
+            |target: &Id, label: &Option<String>| target == to && label.as_deref() == action;
+        if let Some(index) = self.indices.get(from) {
+            let successors = &mut self.states[*index].successors;
+            let amount = successors.len();
+            successors.retain(|(target, label)| !matches(target, label));
+            successors.len() != amount
+        } else {
+            let amount = self.pending_transitions.len();
+            self.pending_transitions
+                .retain(|(source, target, label)| source != from || !matches(target, label));
+            self.pending_transitions.len() != amount
         }
-        symbol
     }
 
-    pub fn create_büchi(
+    /// All transitions as source, target and action: first those of the states in the order
+    /// they were added, then those from unknown states.
+    pub fn transitions(&self) -> impl Iterator<Item = (&Id, &Id, Option<&str>)> + '_ {
+        self.states
+            .iter()
+            .flat_map(|state| {
+                state
+                    .successors
+                    .iter()
+                    .map(move |(to, action)| (&state.id, to, action.as_deref()))
+            })
+            .chain(
+                self.pending_transitions
+                    .iter()
+                    .map(|(from, to, action)| (from, to, action.as_deref())),
+            )
+    }
+
+    /// Outgoing transitions of a state as target and action.
+    pub fn successors(&self, id: &Id) -> impl Iterator<Item = (&Id, Option<&str>)> + '_ {
+        self.state(id)
+            .into_iter()
+            .flat_map(|state| state.successors.iter())
+            .map(|(to, action)| (to, action.as_deref()))
+    }
+
+    /// Incoming transitions of a state as source and action. Unlike [`successors`], this
+    /// looks at all transitions of the model.
+    ///
+    /// [`successors`]: KripkeBuilder::successors
+    pub fn predecessors<'a>(
+        &'a self,
+        id: &'a Id,
+    ) -> impl Iterator<Item = (&'a Id, Option<&'a str>)> + 'a {
+        self.transitions()
+            .filter(move |(_, to, _)| *to == id)
+            .map(|(from, _, action)| (from, action))
+    }
+
+    /// Collects all structural problems of the model: duplicate state ids, a missing start
+    /// state, transitions from or to unknown states and, with [`DeadlockPolicy::Error`],
+    /// deadlocks. The model is valid if the list is empty.
+    pub fn validate(&self) -> Vec<ModelCheckingError<Id>> {
+        let mut errors = Vec::<ModelCheckingError<Id>>::new();
+        for id in &self.duplicate_ids {
+            errors.push(ModelCheckingError::new(
+                ModelCheckingErrorKind::DuplicateStateId(id.clone()),
+            ));
+        }
+        if !self.states.iter().any(|state| state.start) {
+            errors.push(ModelCheckingError::new(
+                ModelCheckingErrorKind::ModelNoStart,
+            ));
+        }
+        for (from, to, _) in &self.pending_transitions {
+            errors.push(ModelCheckingError::new(
+                ModelCheckingErrorKind::DanglingTransitionSource {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+            ));
+            if !self.indices.contains_key(to) {
+                errors.push(ModelCheckingError::new(
+                    ModelCheckingErrorKind::DanglingTransitionTarget {
+                        from: from.clone(),
+                        to: to.clone(),
+                    },
+                ));
+            }
+        }
+        for state in &self.states {
+            for (to, _) in &state.successors {
+                if !self.indices.contains_key(to) {
+                    errors.push(ModelCheckingError::new(
+                        ModelCheckingErrorKind::DanglingTransitionTarget {
+                            from: state.id.clone(),
+                            to: to.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+        if self.deadlock_policy == DeadlockPolicy::Error {
+            for id in self.deadlock_states() {
+                errors.push(ModelCheckingError::new(ModelCheckingErrorKind::Deadlock(
+                    id,
+                )));
+            }
+        }
+        errors
+    }
+
+    /// Id of the state with the given index, see [`KripkeNode::State`].
+    pub(crate) fn id_of(&self, index: usize) -> &Id {
+        &self.states[index].id
+    }
+
+    /// Index of the state with the given id, see [`KripkeNode::State`].
+    pub(crate) fn index_of(&self, id: &Id) -> Option<usize> {
+        self.indices.get(id).copied()
+    }
+
+    /// Ids of all states without an outgoing transition, in the order they were added.
+    pub(crate) fn deadlock_states(&self) -> Vec<Id> {
+        self.states
+            .iter()
+            .filter(|state| state.successors.is_empty())
+            .map(|state| state.id.clone())
+            .collect()
+    }
+
+    /// Ids of all states that cannot be reached from a start state, in the order they were
+    /// added.
+    pub(crate) fn unreachable_states(&self) -> Vec<Id> {
+        let mut reached = BitVec::from_elem(self.states.len(), false);
+        let mut stack = Vec::<usize>::new();
+        for (index, state) in self.states.iter().enumerate() {
+            if state.start {
+                reached.set(index, true);
+                stack.push(index);
+            }
+        }
+        while let Some(index) = stack.pop() {
+            for (to, _) in &self.states[index].successors {
+                if let Some(next) = self.indices.get(to) {
+                    if !reached.get(*next).unwrap() {
+                        reached.set(*next, true);
+                        stack.push(*next);
+                    }
+                }
+            }
+        }
+        reached
+            .iter()
+            .enumerate()
+            .filter(|(_, reached)| !reached)
+            .map(|(index, _)| self.states[index].id.clone())
+            .collect()
+    }
+
+    /// All atomic propositions that hold in at least one state.
+    pub(crate) fn propositions(&self) -> BTreeSet<String> {
+        self.states
+            .iter()
+            .flat_map(|state| state.aps.iter().cloned())
+            .collect()
+    }
+
+    /// All actions that label at least one transition.
+    pub(crate) fn actions(&self) -> BTreeSet<String> {
+        self.transitions()
+            .filter_map(|(_, _, action)| action.map(str::to_string))
+            .collect()
+    }
+
+    /// Symbol of entering the state with index `to`, through a transition labelled with
+    /// `action` if there is one.
+    pub(crate) fn symbol_of(
+        &self,
+        to: usize,
+        action: Option<&str>,
+        ap_map: &HashMap<String, u8>,
+    ) -> u64 {
+        let labels = self.states[to].aps.iter().map(String::as_str);
+        symbol_of_labels(labels.chain(action), ap_map)
+    }
+
+    /// Indices of the start states, in the order they were added.
+    pub(crate) fn start_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.states.len()).filter(|index| self.states[*index].start)
+    }
+
+    /// Successors of the state with index `from` as index and action.
+    pub(crate) fn successor_indices(
+        &self,
+        from: usize,
+    ) -> impl Iterator<Item = (usize, Option<&str>)> + '_ {
+        self.states[from]
+            .successors
+            .iter()
+            .map(|(to, action)| (self.indices[to], action.as_deref()))
+    }
+
+    /// Builds the automaton of the model. With [`DeadlockPolicy::Finite`], the proposition
+    /// `ap_map.len()` holds in every state but the sink.
+    pub(crate) fn create_büchi(
         &self,
         ap_map: &HashMap<String, u8>,
-    ) -> Result<Büchi<u64>, ModelCheckingError> {
+    ) -> Result<Büchi<KripkeNode>, ModelCheckingError<Id>> {
+        if let Some(error) = self.validate().into_iter().next() {
+            return Err(error);
+        }
+
+        let alive: u64 = if self.deadlock_policy == DeadlockPolicy::Finite {
+            1 << ap_map.len()
+        } else {
+            0
+        };
+
+        // Internal state of the state with index i is i + 2.
         // TODO Only include dead-state if necessary.
         let amount_states = self.states.len() + 2;
         let mut transitions = Transitions::for_states(amount_states);
         let mut state_infos = Vec::with_capacity(amount_states);
-        state_infos.push(u64::MAX);
-        state_infos.push(u64::MAX - 1);
-
-        let mut state_map = HashMap::<u64, u64>::with_capacity(amount_states);
-        let mut has_start = false;
+        state_infos.push(KripkeNode::Initial);
+        state_infos.push(KripkeNode::DeadSink);
 
-        for state in self.states.values() {
-            let current_id = state_infos.len() as u64;
-            state_map.insert(state.id, current_id);
-            state_infos.push(state.id);
+        for (index, state) in self.states.iter().enumerate() {
+            state_infos.push(KripkeNode::State(index));
             if state.start {
-                has_start = true;
                 transitions.add(
                     0,
-                    Self::get_symbol_from_string_aps_with_ap_map(&state.aps, ap_map),
-                    current_id,
+                    self.symbol_of(index, None, ap_map) | alive,
+                    index as u64 + 2,
                 );
             }
         }
 
-        if !has_start {
-            return Err(ModelCheckingError::new(
-                ModelCheckingErrorKind::ModelNoStart,
-            ));
+        for (index1, state) in self.states.iter().enumerate() {
+            for (to, action) in &state.successors {
+                let index2 = *self.indices.get(to).unwrap();
+                let symbol = self.symbol_of(index2, action.as_deref(), ap_map) | alive;
+                transitions.add(index1 as u64 + 2, symbol, index2 as u64 + 2);
+            }
         }
 
-        let mut has_successor = BitVec::from_elem(amount_states, false);
-        has_successor.set(0, true);
-        has_successor.set(1, true);
-
-        for (state1, state2) in self.transitions.iter() {
-            let Some(internal_state1) = state_map.get(state1) else {
-                return Err(ModelCheckingError::new(
-                    ModelCheckingErrorKind::ModelInvalid,
-                ));
-            };
-            let Some(internal_state2) = state_map.get(state2) else {
-                return Err(ModelCheckingError::new(
-                    ModelCheckingErrorKind::ModelInvalid,
-                ));
-            };
-            let Some(target_state) = self.states.get(state2) else {
-                return Err(ModelCheckingError::new(
-                    ModelCheckingErrorKind::ModelInvalid,
-                ));
-            };
-            transitions.add(
-                *internal_state1,
-                Self::get_symbol_from_string_aps_with_ap_map(&target_state.aps, ap_map),
-                *internal_state2,
-            );
-            has_successor.set(*internal_state1 as usize, true);
-        }
-
-        if !has_successor.all() {
-            // Make structure total.
-            has_successor
-                .iter()
-                .enumerate()
-                .filter(|(_, x)| !*x)
-                .for_each(|(id, _)| {
-                    transitions.add(id as u64, 0, 1);
-                });
+        // Make structure total.
+        for (index, state) in self.states.iter().enumerate() {
+            if !state.successors.is_empty() {
+                continue;
+            }
+            let internal_state = index as u64 + 2;
+            if self.deadlock_policy == DeadlockPolicy::Stutter {
+                let symbol = self.symbol_of(index, None, ap_map);
+                transitions.add(internal_state, symbol, internal_state);
+            } else {
+                transitions.add(internal_state, 0, 1);
+            }
         }
         transitions.add(1, 0, 1);
 
         Ok(Büchi::new(
             state_infos,
-            ap_map.len() as u8,
+            ap_map.len() as u8 + u8::from(alive != 0),
             0,
             transitions,
             BitVec::from_elem(amount_states, true),
         ))
     }
 }
+
+/// Symbol in which exactly the propositions of `labels` that `ap_map` names hold. Labels are
+/// the propositions of a state and the action of the transition into it.
+pub(crate) fn symbol_of_labels<'a>(
+    labels: impl IntoIterator<Item = &'a str>,
+    ap_map: &HashMap<String, u8>,
+) -> u64 {
+    let mut symbol = 0;
+    for label in labels {
+        if let Some(ap) = ap_map.get(label) {
+            symbol |= 1 << ap;
+        }
+    }
+    symbol
+}
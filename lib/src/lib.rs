@@ -1,30 +1,136 @@
 mod buechi;
+mod composition;
+mod counterexample;
+mod formula;
+mod guarded;
 mod kripke;
+mod lint;
+mod ltlf;
+mod minimisation;
 mod model_checking_error;
+mod monitor;
 mod parsing;
+pub mod patterns;
+mod specification;
+mod transition_system;
 
-pub use kripke::KripkeBuilder;
+pub use buechi::reduction::ReductionStatistics;
+pub use composition::{compose_asynchronous, compose_synchronous};
+pub use counterexample::Counterexample;
+pub use formula::{always, ap, eventually, next, Formula};
+pub use guarded::program_error::{ProgramError, ProgramErrorKind};
+pub use guarded::{GeneratedModel, Program, Value};
+pub use kripke::{DeadlockPolicy, KripkeBuilder};
+pub use lint::{lint, LintWarning, LintWarningKind};
+pub use ltlf::{ltlf_check_trace, ltlf_model_check};
+pub use minimisation::{ltl_model_check_minimised, Equivalence, Quotient};
 pub use model_checking_error::ModelCheckingError;
 pub use model_checking_error::ModelCheckingErrorKind;
+pub use monitor::{Monitor, Verdict};
+pub use parsing::parsing_error::{ErrorKind as ParsingErrorKind, ParsingError, Span};
+pub use parsing::{Dialect, LTLFormula};
+pub use specification::specification_error::{SpecificationError, SpecificationErrorKind};
+pub use specification::Specification;
+pub use transition_system::{ltl_model_check_system, TransitionSystem};
 
 extern crate bit_vec;
 
-use buechi::ltl_to_buechi::ltl_to_büchi;
-use buechi::Büchi;
-use parsing::LTLFormula;
+use buechi::ltl_to_buechi::ltl_to_reduced_büchi;
+use std::collections::HashMap;
+use std::hash::Hash;
 
-pub fn ltl_model_check(
-    ks: KripkeBuilder,
+/// Returns all syntax errors of `formula`, sorted by position. The list is empty if the formula
+/// can be checked.
+pub fn formula_diagnostics(formula: &str) -> Vec<ParsingError> {
+    parsing::parse_with_diagnostics(formula).2
+}
+
+/// Translates `formula` from the syntax of one [`Dialect`] into another.
+pub fn translate_formula(
+    formula: &str,
+    from: Dialect,
+    to: Dialect,
+) -> Result<String, ModelCheckingError> {
+    let (ltl, ap_map) = parsing::parse_in(formula, from)?;
+    parsing::printer::print(&ltl, &ap_map, to)
+        .map_err(|ap| ModelCheckingError::new(ModelCheckingErrorKind::FormulaAPNotExpressible(ap)))
+}
+
+/// Statistics of reducing the generalized Büchi automaton that [`ltl_model_check`] builds for
+/// the negation of `formula`.
+pub fn formula_automaton_statistics(
     formula: &str,
-) -> Result<Option<Vec<(u64, (u64, u8))>>, ModelCheckingError> {
+) -> Result<ReductionStatistics, ModelCheckingError> {
     let (ltl, ap_map) = parsing::parse(formula)?;
+    let (_, statistics) = ltl_to_reduced_büchi(&LTLFormula::not(ltl), ap_map.len() as u8);
+    Ok(statistics)
+}
+
+pub fn ltl_model_check<Id: Hash + Eq + Clone>(
+    ks: KripkeBuilder<Id>,
+    formula: &str,
+) -> Result<Option<Counterexample<Id>>, ModelCheckingError<Id>> {
+    let (ltl, ap_map) = parsing::parse(formula)?;
+    ltl_model_check_formula(ks, &ltl, &ap_map)
+}
+
+/// Like [`ltl_model_check`], but takes a formula that is already built, for example by
+/// [`Formula::into_parts`]. `ap_map` has to name the propositions `0..ap_map.len()`, which
+/// include all propositions of `ltl`.
+pub fn ltl_model_check_formula<Id: Hash + Eq + Clone>(
+    ks: KripkeBuilder<Id>,
+    ltl: &LTLFormula,
+    ap_map: &HashMap<String, u8>,
+) -> Result<Option<Counterexample<Id>>, ModelCheckingError<Id>> {
+    if ap_map.is_empty() {
+        return Err(ModelCheckingError::new(
+            ModelCheckingErrorKind::FormulaNoAPs,
+        ));
+    }
+    let mut named = vec![false; ap_map.len().max(ltl.max_ap() as usize + 1)];
+    for ap in ap_map.values() {
+        if let Some(named) = named.get_mut(*ap as usize) {
+            *named = true;
+        }
+    }
+    if let Some(ap) = named.iter().position(|named| !named) {
+        return Err(ModelCheckingError::new(
+            ModelCheckingErrorKind::FormulaUnnamedAP(ap as u8),
+        ));
+    }
+
+    let finite = ks.deadlock_policy() == DeadlockPolicy::Finite;
+    let ltl = if finite {
+        ltl.to_finite_semantics(ap_map.len() as u8)
+    } else {
+        ltl.clone()
+    };
     let notltl = LTLFormula::Not(Box::new(ltl));
 
-    let model = ks.create_büchi(&ap_map)?;
+    let model = ks.create_büchi(ap_map)?;
 
-    let generalized_büchi = ltl_to_büchi(&notltl);
-    let büchi = Büchi::from_generalized_büchi(generalized_büchi);
+    // Propositions named in `ap_map` need not occur in the formula, but the model sets them.
+    let (büchi, _) = ltl_to_reduced_büchi(&notltl, ap_map.len() as u8 + u8::from(finite));
     let product = buechi::product::product(&model, &büchi);
     let opt_loop = product.get_loop();
-    Ok(opt_loop)
+    Ok(opt_loop.map(|lasso| Counterexample::from_lasso(lasso, &ks)))
+}
+
+/// Generates the state space of a guarded-command `program` and checks it against `formula`.
+///
+/// Atomic propositions of the formula may name boolean variables, labels and actions of the
+/// program, or be boolean expressions over its variables in quotes, like `"x > 3"`. The
+/// generated model is returned alongside the result, so that states of a counterexample can be
+/// printed with [`GeneratedModel::format_state`].
+pub fn ltl_model_check_program(
+    program: &str,
+    formula: &str,
+) -> Result<(GeneratedModel, Option<Counterexample>), ModelCheckingError> {
+    let (_, ap_map) = parsing::parse(formula)?;
+    let propositions: Vec<String> = ap_map.into_keys().collect();
+    let model = Program::parse(program)
+        .and_then(|program| program.generate(&propositions))
+        .map_err(|err| ModelCheckingError::new(ModelCheckingErrorKind::ProgramError(err)))?;
+    let result = ltl_model_check(model.kripke_builder().clone(), formula)?;
+    Ok((model, result))
 }
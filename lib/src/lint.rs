@@ -0,0 +1,153 @@
+use crate::{parsing, KripkeBuilder, ModelCheckingError};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LintWarningKind<Id = u64> {
+    /// The state has no successor. How the checker treats it depends on the
+    /// [`DeadlockPolicy`](crate::DeadlockPolicy) of the model.
+    Deadlock(Id),
+    /// The state cannot be reached from any start state and does not influence the result.
+    UnreachableState(Id),
+    /// The proposition occurs in the formula, but neither holds in any state nor labels any
+    /// transition, so it is always false. `suggestion` is a similarly named proposition of
+    /// the model.
+    UnknownFormulaProposition {
+        name: String,
+        suggestion: Option<String>,
+    },
+    /// The proposition holds in some state, but is not used by the formula.
+    UnusedModelProposition(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LintWarning<Id = u64> {
+    kind: LintWarningKind<Id>,
+}
+
+impl<Id> LintWarning<Id> {
+    pub fn new(kind: LintWarningKind<Id>) -> LintWarning<Id> {
+        LintWarning { kind }
+    }
+
+    pub fn kind(&self) -> &LintWarningKind<Id> {
+        &self.kind
+    }
+}
+
+impl<Id: Display> Display for LintWarning<Id> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            LintWarningKind::Deadlock(id) => write!(f, "State {} has no successor", id),
+            LintWarningKind::UnreachableState(id) => {
+                write!(f, "State {} is not reachable from a start state", id)
+            }
+            LintWarningKind::UnknownFormulaProposition { name, suggestion } => {
+                write!(f, "'{}' does not occur in the model", name)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            LintWarningKind::UnusedModelProposition(name) => {
+                write!(f, "'{}' is not used by the formula", name)
+            }
+        }
+    }
+}
+
+/// Looks for parts of the model and formula that are valid, but probably not intended.
+/// Warnings are ordered by kind, then by the order states were added or by name.
+pub fn lint<Id: Hash + Eq + Clone>(
+    ks: &KripkeBuilder<Id>,
+    formula: &str,
+) -> Result<Vec<LintWarning<Id>>, ModelCheckingError<Id>> {
+    let (_, ap_map) = parsing::parse(formula)?;
+    let mut warnings = Vec::<LintWarning<Id>>::new();
+
+    for id in ks.deadlock_states() {
+        warnings.push(LintWarning::new(LintWarningKind::Deadlock(id)));
+    }
+    for id in ks.unreachable_states() {
+        warnings.push(LintWarning::new(LintWarningKind::UnreachableState(id)));
+    }
+
+    let propositions = ks.propositions();
+    let actions = ks.actions();
+    let mut formula_aps: Vec<&String> = ap_map.keys().collect();
+    formula_aps.sort();
+    for name in &formula_aps {
+        if !propositions.contains(*name) && !actions.contains(*name) {
+            let suggestion = propositions
+                .iter()
+                .chain(actions.iter())
+                .map(|candidate| (edit_distance(name, candidate), candidate))
+                .filter(|(distance, candidate)| *distance <= max_typo_distance(candidate))
+                .min()
+                .map(|(_, candidate)| candidate.clone());
+            warnings.push(LintWarning::new(
+                LintWarningKind::UnknownFormulaProposition {
+                    name: (*name).clone(),
+                    suggestion,
+                },
+            ));
+        }
+    }
+    for name in propositions {
+        if !ap_map.contains_key(&name) {
+            warnings.push(LintWarning::new(LintWarningKind::UnusedModelProposition(
+                name,
+            )));
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Names this far apart are not considered typos of each other.
+fn max_typo_distance(name: &str) -> usize {
+    (name.chars().count() / 3).max(1)
+}
+
+/// Edit distance between `a` and `b`, where inserting, removing or replacing a character and
+/// swapping two adjacent characters cost one edit each (optimal string alignment distance).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j - 1] + cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("ready", "ready"), 0);
+        assert_eq!(edit_distance("raedy", "ready"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
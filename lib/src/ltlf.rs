@@ -0,0 +1,267 @@
+//! LTL over finite traces (LTLf). `X phi` requires a next state, while `WX phi` also holds in
+//! the last state. Formulas are translated to a finite automaton, whose states are the
+//! obligations for the rest of the trace. The automaton is built on the fly while it is run.
+
+use crate::parsing::{self, LTLFormula};
+use crate::{Counterexample, KripkeBuilder, ModelCheckingError};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Formula in negation normal form, in which only propositions are negated.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Nnf {
+    AP(u8),
+    NotAP(u8),
+    And(Box<Nnf>, Box<Nnf>),
+    Or(Box<Nnf>, Box<Nnf>),
+    Next(Box<Nnf>),
+    WeakNext(Box<Nnf>),
+    Until(Box<Nnf>, Box<Nnf>),
+    Release(Box<Nnf>, Box<Nnf>),
+}
+
+impl Nnf {
+    /// Negation normal form of `ltl`, or of `!ltl` if `negated`.
+    fn new(ltl: &LTLFormula, negated: bool) -> Nnf {
+        let new = |phi: &LTLFormula, negated| Box::new(Nnf::new(phi, negated));
+        match (ltl, negated) {
+            (LTLFormula::AP(ap), false) => Nnf::AP(*ap),
+            (LTLFormula::AP(ap), true) => Nnf::NotAP(*ap),
+            (LTLFormula::Not(phi), _) => Nnf::new(phi, !negated),
+            (LTLFormula::And(phi1, phi2), false) => Nnf::And(new(phi1, false), new(phi2, false)),
+            (LTLFormula::And(phi1, phi2), true) => Nnf::Or(new(phi1, true), new(phi2, true)),
+            (LTLFormula::Next(phi), false) => Nnf::Next(new(phi, false)),
+            (LTLFormula::Next(phi), true) => Nnf::WeakNext(new(phi, true)),
+            (LTLFormula::Until(false, phi1, phi2), false) => {
+                Nnf::Until(new(phi1, false), new(phi2, false))
+            }
+            (LTLFormula::Until(false, phi1, phi2), true) => {
+                Nnf::Release(new(phi1, true), new(phi2, true))
+            }
+            // phi1 W phi2 = phi2 R (phi1 | phi2)
+            (LTLFormula::Until(true, phi1, phi2), false) => Nnf::Release(
+                new(phi2, false),
+                Box::new(Nnf::Or(new(phi1, false), new(phi2, false))),
+            ),
+            // !(phi1 W phi2) = !phi2 U (!phi1 & !phi2)
+            (LTLFormula::Until(true, phi1, phi2), true) => Nnf::Until(
+                new(phi2, true),
+                Box::new(Nnf::And(new(phi1, true), new(phi2, true))),
+            ),
+        }
+    }
+}
+
+/// Formulas that have to hold from the next position on, and whether a next position is
+/// required for them.
+type Obligations = BTreeSet<(bool, Nnf)>;
+
+/// Unions of one choice from each of both alternatives.
+fn product(left: Vec<Obligations>, right: &[Obligations]) -> Vec<Obligations> {
+    left.iter()
+        .flat_map(|left| right.iter().map(move |right| left | right))
+        .collect()
+}
+
+/// Alternatives of obligations under which `phi` holds at a position labelled with `symbol`.
+fn expand(phi: &Nnf, symbol: u64) -> Vec<Obligations> {
+    match phi {
+        Nnf::AP(ap) if symbol & (1 << ap) != 0 => vec![Obligations::new()],
+        Nnf::NotAP(ap) if symbol & (1 << ap) == 0 => vec![Obligations::new()],
+        Nnf::AP(_) | Nnf::NotAP(_) => vec![],
+        Nnf::And(phi1, phi2) => product(expand(phi1, symbol), &expand(phi2, symbol)),
+        Nnf::Or(phi1, phi2) => {
+            let mut alternatives = expand(phi1, symbol);
+            alternatives.extend(expand(phi2, symbol));
+            alternatives
+        }
+        Nnf::Next(phi) => vec![Obligations::from([(true, (**phi).clone())])],
+        Nnf::WeakNext(phi) => vec![Obligations::from([(false, (**phi).clone())])],
+        // phi1 U phi2 = phi2 | phi1 & X (phi1 U phi2)
+        Nnf::Until(phi1, phi2) => {
+            let mut alternatives = expand(phi2, symbol);
+            alternatives.extend(product(
+                expand(phi1, symbol),
+                &[Obligations::from([(true, phi.clone())])],
+            ));
+            alternatives
+        }
+        // phi1 R phi2 = phi2 & (phi1 | WX (phi1 R phi2))
+        Nnf::Release(phi1, phi2) => {
+            let mut released = expand(phi1, symbol);
+            released.push(Obligations::from([(false, phi.clone())]));
+            product(expand(phi2, symbol), &released)
+        }
+    }
+}
+
+/// Nondeterministic finite automaton accepting the finite traces of a formula. State `0`
+/// requires the formula at the first position, so the empty trace is rejected.
+struct FiniteAutomaton {
+    states: Vec<Obligations>,
+    indices: HashMap<Obligations, usize>,
+    transitions: HashMap<(usize, u64), Vec<usize>>,
+}
+
+impl FiniteAutomaton {
+    fn new(ltl: &LTLFormula) -> FiniteAutomaton {
+        let initial = Obligations::from([(true, Nnf::new(ltl, false))]);
+        FiniteAutomaton {
+            states: vec![initial.clone()],
+            indices: HashMap::from([(initial, 0)]),
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Whether a trace may end in `state`, i.e. no obligation requires a next position.
+    fn is_accepting(&self, state: usize) -> bool {
+        self.states[state].iter().all(|(strong, _)| !strong)
+    }
+
+    /// Successors of `state` when reading a position labelled with `symbol`.
+    fn successors(&mut self, state: usize, symbol: u64) -> &[usize] {
+        if !self.transitions.contains_key(&(state, symbol)) {
+            let mut alternatives = vec![Obligations::new()];
+            for (_, phi) in &self.states[state] {
+                alternatives = product(expand(phi, symbol), &alternatives);
+            }
+            let mut successors = BTreeSet::new();
+            for mut obligations in alternatives {
+                // X phi & WX phi is X phi.
+                let strong: Vec<Nnf> = obligations
+                    .iter()
+                    .filter(|(strong, _)| *strong)
+                    .map(|(_, phi)| phi.clone())
+                    .collect();
+                for phi in strong {
+                    obligations.remove(&(false, phi));
+                }
+                let next = self.states.len();
+                let index = *self.indices.entry(obligations.clone()).or_insert(next);
+                if index == next {
+                    self.states.push(obligations);
+                }
+                successors.insert(index);
+            }
+            self.transitions
+                .insert((state, symbol), successors.into_iter().collect());
+        }
+        &self.transitions[&(state, symbol)]
+    }
+}
+
+/// Checks `formula` with LTLf semantics on all finite paths of `ks` that begin in a start state
+/// and end in a final state, see [`KripkeBuilder::set_final`]. States without a successor are
+/// final as well. Returns a violating path as a [`Counterexample`] without cycle.
+///
+/// Actions of transitions can be used in the formula like with [`crate::ltl_model_check`].
+pub fn ltlf_model_check<Id: Hash + Eq + Clone>(
+    ks: KripkeBuilder<Id>,
+    formula: &str,
+) -> Result<Option<Counterexample<Id>>, ModelCheckingError<Id>> {
+    let (ltl, ap_map) = parsing::parse(formula)?;
+    if let Some(error) = ks.validate().into_iter().next() {
+        return Err(error);
+    }
+    let mut automaton = FiniteAutomaton::new(&LTLFormula::not(ltl));
+
+    // Breadth-first search through the product for a shortest violating path.
+    let mut parents = HashMap::<(usize, usize), Option<(usize, usize)>>::new();
+    let mut queue = VecDeque::<(usize, usize)>::new();
+    for start in ks.start_indices() {
+        let symbol = ks.symbol_of(start, None, &ap_map);
+        for state in automaton.successors(0, symbol).to_vec() {
+            if parents.insert((start, state), None).is_none() {
+                queue.push_back((start, state));
+            }
+        }
+    }
+    while let Some((index, state)) = queue.pop_front() {
+        if automaton.is_accepting(state) && ks.is_final(ks.id_of(index)) == Some(true) {
+            let mut path = vec![ks.id_of(index).clone()];
+            let mut current = (index, state);
+            while let Some(parent) = parents[&current] {
+                path.push(ks.id_of(parent.0).clone());
+                current = parent;
+            }
+            path.reverse();
+            let path = path.into_iter().map(Some).collect();
+            let deadlocks = ks.deadlock_states();
+            return Ok(Some(Counterexample::from_path(path, vec![], |id| {
+                deadlocks.contains(id)
+            })));
+        }
+        for (to, action) in ks.successor_indices(index) {
+            let symbol = ks.symbol_of(to, action, &ap_map);
+            for next in automaton.successors(state, symbol).to_vec() {
+                if let Entry::Vacant(entry) = parents.entry((to, next)) {
+                    entry.insert(Some((index, state)));
+                    queue.push_back((to, next));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Whether the finite `trace`, given as the propositions of each position, satisfies
+/// `formula` with LTLf semantics. The empty trace satisfies no formula.
+pub fn ltlf_check_trace(formula: &str, trace: &[Vec<String>]) -> Result<bool, ModelCheckingError> {
+    let (ltl, ap_map) = parsing::parse(formula)?;
+    let mut automaton = FiniteAutomaton::new(&ltl);
+    let mut states = BTreeSet::from([0]);
+    for position in trace {
+        let symbol = position
+            .iter()
+            .filter_map(|ap| ap_map.get(ap))
+            .fold(0u64, |symbol, ap| symbol | 1 << ap);
+        states = states
+            .into_iter()
+            .flat_map(|state| automaton.successors(state, symbol).to_vec())
+            .collect();
+    }
+    Ok(states
+        .into_iter()
+        .any(|state| automaton.is_accepting(state)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(formula: &str, trace: &[&[&str]]) -> bool {
+        let trace: Vec<Vec<String>> = trace
+            .iter()
+            .map(|position| position.iter().map(|ap| ap.to_string()).collect())
+            .collect();
+        ltlf_check_trace(formula, &trace).unwrap()
+    }
+
+    #[test]
+    fn test_next() {
+        assert!(check("X a", &[&[], &["a"]]));
+        assert!(!check("X a", &[&["a"]]));
+        assert!(check("WX a", &[&["a"]]));
+        assert!(!check("WX a", &[&[], &[]]));
+        assert!(check("G (a -> WX b)", &[&["a"], &["b", "a"]]));
+        assert!(!check("G (a -> X b)", &[&["a"], &["b", "a"]]));
+        assert!(!check("a | !a", &[]));
+    }
+
+    #[test]
+    fn test_temporal() {
+        let trace: &[&[&str]] = &[&["req"], &["busy"], &["grant"]];
+        assert!(check("G (req -> F grant)", trace));
+        assert!(!check(
+            "G (req -> F grant)",
+            &[&["req"], &["grant"], &["req"]]
+        ));
+        assert!(check("req U grant", &[&["req"], &["req"], &["grant"]]));
+        assert!(!check("req U grant", &[&["req"], &["req"]]));
+        assert!(check("req W grant", &[&["req"], &["req"]]));
+        assert!(check("grant R !busy", &[&["req"], &[]]));
+        assert!(check("F (grant & WX false)", trace));
+        assert!(!check("F G busy", trace));
+    }
+}
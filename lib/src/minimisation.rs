@@ -0,0 +1,351 @@
+use crate::{
+    ltl_model_check, parsing, Counterexample, DeadlockPolicy, KripkeBuilder, ModelCheckingError,
+    ModelCheckingErrorKind,
+};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+mod partition;
+
+/// Equivalence by which [`Quotient`] merges states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Equivalence {
+    /// Strong bisimulation, which preserves every formula.
+    Bisimulation,
+    /// Divergence-sensitive stuttering bisimulation, which only preserves formulas without X,
+    /// but also merges states along paths on which the propositions do not change.
+    StutteringBisimulation,
+}
+
+/// Quotient of a model under an [`Equivalence`] with respect to the propositions and actions of
+/// a formula.
+///
+/// States of the quotient are numbered from 0. Since an action holds in the state a transition
+/// leads to, a state of the model may belong to several states of the quotient, one for every
+/// action it can be entered by. Labels and actions of the formula are both labels of the
+/// quotient states, which has no labelled transitions.
+pub struct Quotient<Id = u64> {
+    model: KripkeBuilder<usize>,
+    equivalence: Equivalence,
+    ids: Vec<Id>,
+    deadlocks: Vec<bool>,
+    /// Model state of every node. Nodes are the pairs of a state and the relevant action it is
+    /// entered by.
+    nodes: Vec<usize>,
+    successors: Vec<Vec<usize>>,
+    starts: Vec<usize>,
+    block_of: Vec<usize>,
+    blocks: Vec<Vec<Id>>,
+}
+
+impl<Id: Hash + Eq + Clone> Quotient<Id> {
+    /// Computes the quotient of the part of `ks` that is reachable from its start states.
+    ///
+    /// Fails if `ks` is not valid, if `formula` cannot be parsed, or if it contains X and
+    /// `equivalence` is [`Equivalence::StutteringBisimulation`].
+    pub fn new(
+        ks: &KripkeBuilder<Id>,
+        formula: &str,
+        equivalence: Equivalence,
+    ) -> Result<Quotient<Id>, ModelCheckingError<Id>> {
+        let (ltl, ap_map) = parsing::parse(formula)?;
+        if equivalence == Equivalence::StutteringBisimulation && ltl.contains_next() {
+            return Err(ModelCheckingError::new(
+                ModelCheckingErrorKind::FormulaContainsNext,
+            ));
+        }
+        if let Some(error) = ks.validate().into_iter().next() {
+            return Err(error);
+        }
+
+        let ids: Vec<Id> = ks.states().map(|(id, _, _)| id.clone()).collect();
+        let deadlocks: Vec<bool> = ids
+            .iter()
+            .map(|id| ks.successors(id).next().is_none())
+            .collect();
+        let relevant_action = |action: Option<&str>| {
+            action
+                .filter(|action| ap_map.contains_key(*action))
+                .map(str::to_string)
+        };
+
+        let mut nodes = Vec::<(usize, Option<String>)>::new();
+        let mut node_indices = HashMap::<(usize, Option<String>), usize>::new();
+        let mut successors = Vec::<Vec<usize>>::new();
+        let mut queue = VecDeque::<usize>::new();
+        let starts: Vec<usize> = ks
+            .states()
+            .enumerate()
+            .filter(|(_, (_, _, start))| *start)
+            .map(|(index, _)| add_node(&mut nodes, &mut node_indices, &mut queue, (index, None)))
+            .collect();
+        while let Some(node) = queue.pop_front() {
+            let index = nodes[node].0;
+            let mut node_successors = vec![];
+            for (to, action) in ks.successors(&ids[index]) {
+                let to = (ks.index_of(to).unwrap(), relevant_action(action));
+                node_successors.push(add_node(&mut nodes, &mut node_indices, &mut queue, to));
+            }
+            // A deadlock that repeats itself is entered without an action.
+            if deadlocks[index] && ks.deadlock_policy() == DeadlockPolicy::Stutter {
+                let to = (index, None);
+                node_successors.push(add_node(&mut nodes, &mut node_indices, &mut queue, to));
+            }
+            node_successors.sort_unstable();
+            node_successors.dedup();
+            successors.push(node_successors);
+        }
+
+        let labels: Vec<Vec<String>> = nodes
+            .iter()
+            .map(|(index, action)| {
+                let mut labels: Vec<String> = ks
+                    .aps(&ids[*index])
+                    .unwrap()
+                    .iter()
+                    .filter(|ap| ap_map.contains_key(*ap))
+                    .cloned()
+                    .chain(action.clone())
+                    .collect();
+                labels.sort();
+                labels.dedup();
+                labels
+            })
+            .collect();
+        let mut classes = HashMap::<(&[String], bool), usize>::new();
+        let initial: Vec<usize> = labels
+            .iter()
+            .zip(&successors)
+            .map(|(labels, successors)| {
+                let next = classes.len();
+                *classes
+                    .entry((labels, successors.is_empty()))
+                    .or_insert(next)
+            })
+            .collect();
+
+        let block_of = match equivalence {
+            Equivalence::Bisimulation => partition::bisimulation(&successors, &initial),
+            Equivalence::StutteringBisimulation => {
+                partition::stuttering_bisimulation(&successors, &initial)
+            }
+        };
+
+        let amount_blocks = block_of.iter().max().map_or(0, |block| block + 1);
+        let mut model = KripkeBuilder::<usize>::new();
+        model.set_deadlock_policy(ks.deadlock_policy());
+        let mut blocks = vec![Vec::<Id>::new(); amount_blocks];
+        for (node, block) in block_of.iter().enumerate() {
+            if !model.contains_state(block) {
+                model.add_state(labels[node].clone(), *block, false);
+            }
+            let id = &ids[nodes[node].0];
+            if !blocks[*block].contains(id) {
+                blocks[*block].push(id.clone());
+            }
+        }
+        for start in &starts {
+            model.set_start(&block_of[*start], true);
+        }
+        let divergent = partition::divergent_nodes(&successors, &block_of);
+        for (from, tos) in successors.iter().enumerate() {
+            for to in tos {
+                // Steps inside a block are stuttering, the quotient only keeps them if they
+                // can be repeated forever.
+                let stuttering = equivalence == Equivalence::StutteringBisimulation
+                    && block_of[from] == block_of[*to]
+                    && !divergent[from];
+                if !stuttering {
+                    model.add_transition(block_of[from], block_of[*to]);
+                }
+            }
+        }
+
+        Ok(Quotient {
+            model,
+            equivalence,
+            ids,
+            deadlocks,
+            nodes: nodes.into_iter().map(|(index, _)| index).collect(),
+            successors,
+            starts,
+            block_of,
+            blocks,
+        })
+    }
+
+    pub fn model(&self) -> &KripkeBuilder<usize> {
+        &self.model
+    }
+
+    /// States of the model merged into the given state of the quotient.
+    pub fn states_of(&self, state: usize) -> &[Id] {
+        &self.blocks[state]
+    }
+
+    /// Maps a counterexample of the quotient back to a path of the model that violates the
+    /// same formulas.
+    pub fn concretise(&self, counterexample: &Counterexample<usize>) -> Counterexample<Id> {
+        let path: Vec<usize> = counterexample.states().copied().collect();
+        let cycle_start = counterexample.prefix().len();
+        let next_position = |position: usize| {
+            if position + 1 < path.len() {
+                Some(position + 1)
+            } else if counterexample.cycle().is_empty() {
+                None
+            } else {
+                Some(cycle_start)
+            }
+        };
+
+        // Pairs of a node and a position on the path, together with whether a step enters the
+        // start of the cycle.
+        let steps = |(node, position): (usize, usize)| {
+            let mut steps = vec![];
+            for to in &self.successors[node] {
+                let block = self.block_of[*to];
+                if self.equivalence == Equivalence::StutteringBisimulation
+                    && block == path[position]
+                {
+                    steps.push(((*to, position), false));
+                }
+                if let Some(next) = next_position(position) {
+                    if block == path[next] {
+                        steps.push(((*to, next), next == cycle_start));
+                    }
+                }
+            }
+            steps
+        };
+        let roots: Vec<(usize, usize)> = self
+            .starts
+            .iter()
+            .filter(|start| self.block_of[**start] == path[0])
+            .map(|start| (*start, 0))
+            .collect();
+        let model_path = |pairs: Vec<(usize, usize)>| -> Vec<Option<Id>> {
+            pairs
+                .into_iter()
+                .map(|(node, _)| Some(self.ids[self.nodes[node]].clone()))
+                .collect()
+        };
+        let is_deadlock = |id: &Id| {
+            self.ids
+                .iter()
+                .position(|other| other == id)
+                .is_some_and(|index| self.deadlocks[index])
+        };
+
+        if counterexample.cycle().is_empty() {
+            let prefix = search(&roots, &steps, |(node, position)| {
+                position + 1 == path.len() && self.successors[*node].is_empty()
+            })
+            .expect("the quotient is bisimilar to the model");
+            return Counterexample::from_path(model_path(prefix), vec![], is_deadlock);
+        }
+
+        // Look for a reachable step into the cycle start that can be reached again from its
+        // target.
+        let reachable = search_all(&roots, &steps);
+        for pair in reachable {
+            for (to, enters_cycle) in steps(pair) {
+                if !enters_cycle {
+                    continue;
+                }
+                if let Some(mut cycle) = search(&[to], &steps, |other| *other == pair) {
+                    // Both paths end with the pair, the cycle is rotated to start with it.
+                    let mut prefix = search(&roots, &steps, |other| *other == pair).unwrap();
+                    prefix.pop();
+                    cycle.pop();
+                    cycle.insert(0, pair);
+                    return Counterexample::from_path(
+                        model_path(prefix),
+                        model_path(cycle),
+                        is_deadlock,
+                    );
+                }
+            }
+        }
+        unreachable!("the quotient is bisimilar to the model")
+    }
+}
+
+fn add_node(
+    nodes: &mut Vec<(usize, Option<String>)>,
+    node_indices: &mut HashMap<(usize, Option<String>), usize>,
+    queue: &mut VecDeque<usize>,
+    node: (usize, Option<String>),
+) -> usize {
+    *node_indices.entry(node.clone()).or_insert_with(|| {
+        nodes.push(node);
+        queue.push_back(nodes.len() - 1);
+        nodes.len() - 1
+    })
+}
+
+type Step = ((usize, usize), bool);
+
+/// Shortest path from one of the roots to a pair satisfying `target`.
+fn search(
+    roots: &[(usize, usize)],
+    steps: &impl Fn((usize, usize)) -> Vec<Step>,
+    target: impl Fn(&(usize, usize)) -> bool,
+) -> Option<Vec<(usize, usize)>> {
+    let mut parents = HashMap::<(usize, usize), Option<(usize, usize)>>::new();
+    let mut queue = VecDeque::<(usize, usize)>::new();
+    for root in roots {
+        parents.insert(*root, None);
+        queue.push_back(*root);
+    }
+    while let Some(pair) = queue.pop_front() {
+        if target(&pair) {
+            let mut path = vec![pair];
+            while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+                path.push(*parent);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for (to, _) in steps(pair) {
+            if let Entry::Vacant(entry) = parents.entry(to) {
+                entry.insert(Some(pair));
+                queue.push_back(to);
+            }
+        }
+    }
+    None
+}
+
+/// All pairs reachable from the roots, in breadth-first order.
+fn search_all(
+    roots: &[(usize, usize)],
+    steps: &impl Fn((usize, usize)) -> Vec<Step>,
+) -> Vec<(usize, usize)> {
+    let mut reached: Vec<(usize, usize)> = roots.to_vec();
+    let mut seen: HashSet<(usize, usize)> = roots.iter().copied().collect();
+    let mut index = 0;
+    while index < reached.len() {
+        for (to, _) in steps(reached[index]) {
+            if seen.insert(to) {
+                reached.push(to);
+            }
+        }
+        index += 1;
+    }
+    reached
+}
+
+/// Checks `formula` on the quotient of `ks` and maps a counterexample back to `ks`. See
+/// [`Quotient::new`].
+pub fn ltl_model_check_minimised<Id: Hash + Eq + Clone>(
+    ks: KripkeBuilder<Id>,
+    formula: &str,
+    equivalence: Equivalence,
+) -> Result<Option<Counterexample<Id>>, ModelCheckingError<Id>> {
+    let quotient = Quotient::new(&ks, formula, equivalence)?;
+    let result = ltl_model_check(quotient.model().clone(), formula)
+        .map_err(|error| error.map_ids(|state| quotient.states_of(state)[0].clone()))?;
+    Ok(result.map(|counterexample| quotient.concretise(&counterexample)))
+}
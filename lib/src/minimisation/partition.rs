@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// Partition of the nodes `0..n` of a graph into blocks.
+struct Partition {
+    block_of: Vec<usize>,
+    members: Vec<Vec<usize>>,
+    /// Position of every node in the members of its block.
+    position: Vec<usize>,
+}
+
+impl Partition {
+    /// Puts nodes with the same initial class into one block.
+    fn new(initial: &[usize]) -> Partition {
+        let mut blocks = HashMap::<usize, usize>::new();
+        let mut partition = Partition {
+            block_of: Vec::with_capacity(initial.len()),
+            members: vec![],
+            position: Vec::with_capacity(initial.len()),
+        };
+        for (node, class) in initial.iter().enumerate() {
+            let block = *blocks.entry(*class).or_insert_with(|| {
+                partition.members.push(vec![]);
+                partition.members.len() - 1
+            });
+            partition.block_of.push(block);
+            partition.position.push(partition.members[block].len());
+            partition.members[block].push(node);
+        }
+        partition
+    }
+
+    /// Moves the marked nodes of every block into a new block, unless all nodes of the block are
+    /// marked. `marked` must not contain a node twice. Returns the pairs of the split block and
+    /// the new block.
+    fn split(&mut self, marked: &[usize]) -> Vec<(usize, usize)> {
+        let mut by_block = BTreeMap::<usize, Vec<usize>>::new();
+        for node in marked {
+            by_block
+                .entry(self.block_of[*node])
+                .or_default()
+                .push(*node);
+        }
+
+        let mut splits = vec![];
+        for (block, nodes) in by_block {
+            if nodes.len() == self.members[block].len() {
+                continue;
+            }
+            let new_block = self.members.len();
+            self.members.push(Vec::with_capacity(nodes.len()));
+            for node in nodes {
+                let position = self.position[node];
+                self.members[block].swap_remove(position);
+                if let Some(moved) = self.members[block].get(position) {
+                    self.position[*moved] = position;
+                }
+                self.block_of[node] = new_block;
+                self.position[node] = self.members[new_block].len();
+                self.members[new_block].push(node);
+            }
+            splits.push((block, new_block));
+        }
+        splits
+    }
+
+    /// Block of every node, with blocks numbered in the order of their first node.
+    fn into_numbering(self) -> Vec<usize> {
+        let mut numbers = HashMap::<usize, usize>::new();
+        self.block_of
+            .iter()
+            .map(|block| {
+                let next = numbers.len();
+                *numbers.entry(*block).or_insert(next)
+            })
+            .collect()
+    }
+}
+
+fn predecessors(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut predecessors = vec![vec![]; successors.len()];
+    for (from, tos) in successors.iter().enumerate() {
+        for to in tos {
+            predecessors[*to].push(from);
+        }
+    }
+    predecessors
+}
+
+/// Coarsest strong bisimulation of the graph that only relates nodes of the same `initial`
+/// class, computed with the algorithm of Paige and Tarjan in O(m log n). Returns the block of
+/// every node, numbered in the order of their first node.
+pub(crate) fn bisimulation(successors: &[Vec<usize>], initial: &[usize]) -> Vec<usize> {
+    let mut partition = Partition::new(initial);
+
+    // Edges are numbered, each one refers to the counter of edges from its source into the
+    // compound block containing its target.
+    let mut sources = vec![];
+    let mut incoming = vec![vec![]; successors.len()];
+    for (from, tos) in successors.iter().enumerate() {
+        for to in tos {
+            incoming[*to].push(sources.len());
+            sources.push(from);
+        }
+    }
+    let mut counts: Vec<usize> = successors.iter().map(Vec::len).collect();
+    let mut edge_count: Vec<usize> = sources.clone();
+
+    // The algorithm starts with a partition that is stable with respect to all nodes.
+    let alive: Vec<usize> = (0..successors.len())
+        .filter(|node| !successors[*node].is_empty())
+        .collect();
+    partition.split(&alive);
+
+    // Every block is contained in a compound block, the partition is stable with respect to
+    // each of them. Compound blocks of more than one block are refined until none are left.
+    let mut compounds: Vec<Vec<usize>> = vec![(0..partition.members.len()).collect()];
+    let mut compound_of: Vec<usize> = vec![0; partition.members.len()];
+    let mut worklist: Vec<usize> = vec![0];
+
+    while let Some(compound) = worklist.pop() {
+        if compounds[compound].len() < 2 {
+            continue;
+        }
+
+        // Split off the smaller one of two blocks, so that every node is only part of a
+        // splitter O(log n) times.
+        let (first, second) = (compounds[compound][0], compounds[compound][1]);
+        let splitter = if partition.members[first].len() <= partition.members[second].len() {
+            first
+        } else {
+            second
+        };
+        compounds[compound].retain(|block| *block != splitter);
+        if compounds[compound].len() > 1 {
+            worklist.push(compound);
+        }
+        compound_of[splitter] = compounds.len();
+        compounds.push(vec![splitter]);
+
+        // Count the edges of every predecessor into the splitter and remember one of them.
+        let splitter_members = partition.members[splitter].clone();
+        let mut into_splitter = HashMap::<usize, (usize, usize)>::new();
+        let mut pre_splitter = vec![];
+        for node in &splitter_members {
+            for edge in &incoming[*node] {
+                let entry = into_splitter.entry(sources[*edge]).or_insert_with(|| {
+                    pre_splitter.push(sources[*edge]);
+                    (0, *edge)
+                });
+                entry.0 += 1;
+            }
+        }
+        // Predecessors without an edge into the rest of the compound block.
+        let only_splitter: Vec<usize> = pre_splitter
+            .iter()
+            .copied()
+            .filter(|node| {
+                let (count, edge) = into_splitter[node];
+                count == counts[edge_count[edge]]
+            })
+            .collect();
+
+        for marked in [&pre_splitter, &only_splitter] {
+            for (block, new_block) in partition.split(marked) {
+                let compound = compound_of[block];
+                compound_of.push(compound);
+                debug_assert_eq!(compound_of.len(), new_block + 1);
+                compounds[compound].push(new_block);
+                if compounds[compound].len() == 2 {
+                    worklist.push(compound);
+                }
+            }
+        }
+
+        let splitter_counts: HashMap<usize, usize> = pre_splitter
+            .iter()
+            .map(|node| {
+                counts.push(into_splitter[node].0);
+                (*node, counts.len() - 1)
+            })
+            .collect();
+        for node in &splitter_members {
+            for edge in &incoming[*node] {
+                counts[edge_count[*edge]] -= 1;
+                edge_count[*edge] = splitter_counts[&sources[*edge]];
+            }
+        }
+    }
+
+    partition.into_numbering()
+}
+
+/// Coarsest divergence-sensitive stuttering bisimulation of the graph that only relates nodes of
+/// the same `initial` class. Returns the block of every node, numbered in the order of their
+/// first node.
+///
+/// Blocks are split until, for every pair of blocks `B` and `C`, either all or none of the
+/// nodes of `B` can reach `C` by a path through `B`, and either all or none of them have an
+/// infinite path through `B`. This takes O(n m) per split.
+pub(crate) fn stuttering_bisimulation(successors: &[Vec<usize>], initial: &[usize]) -> Vec<usize> {
+    let predecessors = predecessors(successors);
+    let mut partition = Partition::new(initial);
+    while let Some(marked) = find_stuttering_split(successors, &predecessors, &partition) {
+        partition.split(&marked);
+    }
+    partition.into_numbering()
+}
+
+fn find_stuttering_split(
+    successors: &[Vec<usize>],
+    predecessors: &[Vec<usize>],
+    partition: &Partition,
+) -> Option<Vec<usize>> {
+    let block_of = &partition.block_of;
+    let divergent = divergent_nodes(successors, block_of);
+    for (block, members) in partition.members.iter().enumerate() {
+        let diverging: Vec<usize> = members
+            .iter()
+            .copied()
+            .filter(|node| divergent[*node])
+            .collect();
+        if !diverging.is_empty() && diverging.len() < members.len() {
+            return Some(diverging);
+        }
+
+        let mut splitters: Vec<usize> = members
+            .iter()
+            .flat_map(|node| successors[*node].iter().map(|to| block_of[*to]))
+            .filter(|to| *to != block)
+            .collect();
+        splitters.sort_unstable();
+        splitters.dedup();
+        for splitter in splitters {
+            // Backwards search inside the block from the nodes with an edge into the splitter.
+            let mut reaching: Vec<usize> = members
+                .iter()
+                .copied()
+                .filter(|node| successors[*node].iter().any(|to| block_of[*to] == splitter))
+                .collect();
+            let mut reached: Vec<bool> = vec![false; successors.len()];
+            for node in &reaching {
+                reached[*node] = true;
+            }
+            let mut index = 0;
+            while index < reaching.len() {
+                for from in &predecessors[reaching[index]] {
+                    if block_of[*from] == block && !reached[*from] {
+                        reached[*from] = true;
+                        reaching.push(*from);
+                    }
+                }
+                index += 1;
+            }
+            if reaching.len() < members.len() {
+                return Some(reaching);
+            }
+        }
+    }
+    None
+}
+
+/// Whether each node has an infinite path that stays in its block.
+pub(crate) fn divergent_nodes(successors: &[Vec<usize>], block_of: &[usize]) -> Vec<bool> {
+    let predecessors = predecessors(successors);
+    let mut inert_successors: Vec<usize> = successors
+        .iter()
+        .enumerate()
+        .map(|(node, tos)| {
+            tos.iter()
+                .filter(|to| block_of[**to] == block_of[node])
+                .count()
+        })
+        .collect();
+    let mut divergent = vec![true; successors.len()];
+    let mut stuck: Vec<usize> = (0..successors.len())
+        .filter(|node| inert_successors[*node] == 0)
+        .collect();
+    while let Some(node) = stuck.pop() {
+        divergent[node] = false;
+        for from in &predecessors[node] {
+            if block_of[*from] == block_of[node] {
+                inert_successors[*from] -= 1;
+                if inert_successors[*from] == 0 {
+                    stuck.push(*from);
+                }
+            }
+        }
+    }
+    divergent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisimulation() {
+        // 0 -> 1 -> 2 and 3 -> 4 -> 5 are bisimilar chains, 6 -> 7 is shorter.
+        let successors = vec![
+            vec![1],
+            vec![2],
+            vec![],
+            vec![4],
+            vec![5],
+            vec![],
+            vec![7],
+            vec![],
+        ];
+        assert_eq!(
+            bisimulation(&successors, &[0; 8]),
+            vec![0, 1, 2, 0, 1, 2, 1, 2]
+        );
+        assert_eq!(
+            bisimulation(&successors, &[0, 0, 0, 0, 0, 1, 0, 0]),
+            vec![0, 1, 2, 3, 4, 5, 1, 2]
+        );
+
+        // Choosing between 2 and 3 late is not bisimilar to choosing early.
+        let successors = vec![
+            vec![1],
+            vec![2, 3],
+            vec![],
+            vec![],
+            vec![5, 6],
+            vec![2],
+            vec![3],
+        ];
+        assert_eq!(
+            bisimulation(&successors, &[0, 0, 1, 2, 0, 0, 0]),
+            vec![0, 1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_stuttering_bisimulation() {
+        // 0 -> 1 -> 2 and 3 -> 4 only differ in stuttering steps.
+        let successors = vec![vec![1], vec![2], vec![2], vec![4], vec![4]];
+        assert_eq!(
+            stuttering_bisimulation(&successors, &[0, 0, 1, 0, 1]),
+            vec![0, 0, 1, 0, 1]
+        );
+        // 0 can stutter forever, 1 cannot.
+        let successors = vec![vec![0, 1], vec![2], vec![2]];
+        assert_eq!(
+            stuttering_bisimulation(&successors, &[0, 0, 1]),
+            vec![0, 1, 2]
+        );
+        assert_eq!(bisimulation(&successors, &[0, 0, 1]), vec![0, 1, 2]);
+    }
+}
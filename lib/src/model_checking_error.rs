@@ -1,42 +1,135 @@
+use crate::guarded::program_error::ProgramError;
 use crate::parsing::parsing_error::ParsingError;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
 #[derive(Debug, PartialEq)]
-pub enum ModelCheckingErrorKind {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ModelCheckingErrorKind<Id = u64> {
     ModelNoStart,
-    ModelInvalid,
+    DuplicateStateId(Id),
+    DanglingTransitionSource {
+        from: Id,
+        to: Id,
+    },
+    DanglingTransitionTarget {
+        from: Id,
+        to: Id,
+    },
+    Deadlock(Id),
     FormulaNoAPs,
+    FormulaContainsNext,
+    /// The proposition cannot be written in the syntax a formula is translated to.
+    FormulaAPNotExpressible(String),
+    /// A formula that was not parsed uses a proposition without a name.
+    FormulaUnnamedAP(u8),
     FormulaSytaxError(ParsingError),
+    ProgramError(ProgramError),
 }
 
 #[derive(Debug, PartialEq)]
-pub struct ModelCheckingError {
-    kind: ModelCheckingErrorKind,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelCheckingError<Id = u64> {
+    kind: ModelCheckingErrorKind<Id>,
 }
 
-impl ModelCheckingError {
-    pub fn new(kind: ModelCheckingErrorKind) -> ModelCheckingError {
+impl<Id> ModelCheckingError<Id> {
+    pub fn new(kind: ModelCheckingErrorKind<Id>) -> ModelCheckingError<Id> {
         ModelCheckingError { kind }
     }
 
-    pub fn kind(&self) -> &ModelCheckingErrorKind {
+    pub fn kind(&self) -> &ModelCheckingErrorKind<Id> {
         &self.kind
     }
+
+    /// Replaces the state ids of the error.
+    pub(crate) fn map_ids<Other>(self, f: impl Fn(Id) -> Other) -> ModelCheckingError<Other> {
+        let kind = match self.kind {
+            ModelCheckingErrorKind::ModelNoStart => ModelCheckingErrorKind::ModelNoStart,
+            ModelCheckingErrorKind::DuplicateStateId(id) => {
+                ModelCheckingErrorKind::DuplicateStateId(f(id))
+            }
+            ModelCheckingErrorKind::DanglingTransitionSource { from, to } => {
+                ModelCheckingErrorKind::DanglingTransitionSource {
+                    from: f(from),
+                    to: f(to),
+                }
+            }
+            ModelCheckingErrorKind::DanglingTransitionTarget { from, to } => {
+                ModelCheckingErrorKind::DanglingTransitionTarget {
+                    from: f(from),
+                    to: f(to),
+                }
+            }
+            ModelCheckingErrorKind::Deadlock(id) => ModelCheckingErrorKind::Deadlock(f(id)),
+            ModelCheckingErrorKind::FormulaNoAPs => ModelCheckingErrorKind::FormulaNoAPs,
+            ModelCheckingErrorKind::FormulaContainsNext => {
+                ModelCheckingErrorKind::FormulaContainsNext
+            }
+            ModelCheckingErrorKind::FormulaAPNotExpressible(ap) => {
+                ModelCheckingErrorKind::FormulaAPNotExpressible(ap)
+            }
+            ModelCheckingErrorKind::FormulaUnnamedAP(ap) => {
+                ModelCheckingErrorKind::FormulaUnnamedAP(ap)
+            }
+            ModelCheckingErrorKind::FormulaSytaxError(error) => {
+                ModelCheckingErrorKind::FormulaSytaxError(error)
+            }
+            ModelCheckingErrorKind::ProgramError(error) => {
+                ModelCheckingErrorKind::ProgramError(error)
+            }
+        };
+        ModelCheckingError::new(kind)
+    }
 }
 
-impl Error for ModelCheckingError {}
+impl<Id: fmt::Debug + Display> Error for ModelCheckingError<Id> {}
 
-impl Display for ModelCheckingError {
+impl<Id: Display> Display for ModelCheckingError<Id> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let error_msg = match &self.kind {
             ModelCheckingErrorKind::ModelNoStart => "Model has no start",
-            ModelCheckingErrorKind::ModelInvalid => "Model is invalid",
+            ModelCheckingErrorKind::DuplicateStateId(id) => {
+                return write!(f, "State id {} is used more than once", id);
+            }
+            ModelCheckingErrorKind::DanglingTransitionSource { from, to } => {
+                return write!(
+                    f,
+                    "Transition from {} to {} starts at unknown state {}",
+                    from, to, from
+                );
+            }
+            ModelCheckingErrorKind::DanglingTransitionTarget { from, to } => {
+                return write!(
+                    f,
+                    "Transition from {} to {} ends at unknown state {}",
+                    from, to, to
+                );
+            }
+            ModelCheckingErrorKind::Deadlock(id) => {
+                return write!(f, "State {} has no successor", id);
+            }
             ModelCheckingErrorKind::FormulaNoAPs => "Formula must contain an atomic proposition",
+            ModelCheckingErrorKind::FormulaContainsNext => {
+                "Formula must not contain X to be checked up to stuttering"
+            }
+            ModelCheckingErrorKind::FormulaAPNotExpressible(ap) => {
+                return write!(
+                    f,
+                    "Atomic proposition '{}' cannot be written in this syntax",
+                    ap
+                );
+            }
+            ModelCheckingErrorKind::FormulaUnnamedAP(ap) => {
+                return write!(f, "Atomic proposition {} has no name", ap);
+            }
             ModelCheckingErrorKind::FormulaSytaxError(parse) => {
                 return parse.fmt(f);
             }
+            ModelCheckingErrorKind::ProgramError(program) => {
+                return program.fmt(f);
+            }
         };
         write!(f, "{}", error_msg)
     }
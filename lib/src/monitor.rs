@@ -0,0 +1,207 @@
+use crate::buechi::ltl_to_buechi::ltl_to_reduced_büchi;
+use crate::buechi::Büchi;
+use crate::{Formula, LTLFormula, ModelCheckingError};
+use bit_vec::BitVec;
+use std::collections::{BTreeSet, HashMap};
+
+/// Verdict of a [`Monitor`] on the steps read so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Verdict {
+    /// Every continuation satisfies the formula.
+    Satisfied,
+    /// No continuation satisfies the formula.
+    Violated,
+    /// Some continuations satisfy the formula and some do not.
+    Inconclusive,
+}
+
+/// Büchi automaton run on the steps read so far.
+struct Run {
+    büchi: Büchi<(u64, u8)>,
+    non_empty: BitVec,
+    states: BTreeSet<u64>,
+}
+
+impl Run {
+    /// Run of the automaton of `ltl` over the propositions `0..amount_aps`.
+    fn new(ltl: &LTLFormula, amount_aps: u8) -> Run {
+        let (büchi, _) = ltl_to_reduced_büchi(ltl, amount_aps);
+        Run {
+            non_empty: büchi.non_empty_states(),
+            states: BTreeSet::from([büchi.start_state()]),
+            büchi,
+        }
+    }
+
+    fn step(&mut self, symbol: u64) {
+        self.states = self
+            .states
+            .iter()
+            .flat_map(|state| self.büchi.successors_with_symbol(*state, symbol))
+            .collect();
+    }
+
+    /// Whether some continuation is accepted.
+    fn is_non_empty(&self) -> bool {
+        self.states
+            .iter()
+            .any(|state| self.non_empty.get(*state as usize).unwrap())
+    }
+}
+
+/// Runtime monitor of an LTL formula with LTL3 semantics. It reads a trace one step at a time
+/// and reports after each step whether all, none or only some infinite continuations of the
+/// trace satisfy the formula. It runs the Büchi automata of the formula and its negation.
+///
+/// ```
+/// use model_checker::{Monitor, Verdict};
+///
+/// let mut monitor = Monitor::new("req U grant").unwrap();
+/// assert_eq!(monitor.step(&["req"]), Verdict::Inconclusive);
+/// assert_eq!(monitor.step(&["grant"]), Verdict::Satisfied);
+///
+/// let mut monitor = Monitor::new("G !error").unwrap();
+/// assert_eq!(monitor.step(&["ok"]), Verdict::Inconclusive);
+/// assert_eq!(monitor.step(&["error"]), Verdict::Violated);
+/// ```
+pub struct Monitor {
+    ap_map: HashMap<String, u8>,
+    positive: Run,
+    negative: Run,
+}
+
+impl Monitor {
+    pub fn new(formula: &str) -> Result<Monitor, ModelCheckingError> {
+        Ok(Monitor::from_formula(&Formula::parse(formula)?))
+    }
+
+    /// Monitor of `formula`, whose steps may set every proposition its map names, even those
+    /// that do not occur in it.
+    pub fn from_formula(formula: &Formula) -> Monitor {
+        let amount_aps = formula.ap_map().len() as u8;
+        Monitor {
+            ap_map: formula.ap_map().clone(),
+            positive: Run::new(formula.ltl(), amount_aps),
+            negative: Run::new(&LTLFormula::not(formula.ltl().clone()), amount_aps),
+        }
+    }
+
+    /// Number of each proposition, as used by [`Monitor::step_valuation`].
+    pub fn ap_map(&self) -> &HashMap<String, u8> {
+        &self.ap_map
+    }
+
+    /// Reads a step in which the propositions named `aps` hold. Unknown names are ignored.
+    pub fn step<S: AsRef<str>>(&mut self, aps: &[S]) -> Verdict {
+        let valuation = aps
+            .iter()
+            .filter_map(|ap| self.ap_map.get(ap.as_ref()))
+            .fold(0, |valuation, ap| valuation | 1 << ap);
+        self.step_valuation(valuation)
+    }
+
+    /// Reads a step in which proposition `i` holds if bit `i` of `valuation` is set.
+    pub fn step_valuation(&mut self, valuation: u64) -> Verdict {
+        let symbol = valuation
+            & 1u64
+                .checked_shl(self.ap_map.len() as u32)
+                .map_or(u64::MAX, |bit| bit - 1);
+        self.positive.step(symbol);
+        self.negative.step(symbol);
+        self.verdict()
+    }
+
+    /// Verdict on the steps read so far, which is final once it is not
+    /// [`Verdict::Inconclusive`].
+    pub fn verdict(&self) -> Verdict {
+        if !self.positive.is_non_empty() {
+            Verdict::Violated
+        } else if !self.negative.is_non_empty() {
+            Verdict::Satisfied
+        } else {
+            Verdict::Inconclusive
+        }
+    }
+
+    /// Forgets all steps read so far.
+    pub fn reset(&mut self) {
+        for run in [&mut self.positive, &mut self.negative] {
+            run.states = BTreeSet::from([run.büchi.start_state()]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verdicts(formula: &str, trace: &[&[&str]]) -> Vec<Verdict> {
+        let mut monitor = Monitor::new(formula).unwrap();
+        trace.iter().map(|step| monitor.step(step)).collect()
+    }
+
+    #[test]
+    fn test_verdicts() {
+        use Verdict::*;
+
+        assert_eq!(
+            verdicts("F grant", &[&["req"], &["grant"], &[]]),
+            vec![Inconclusive, Satisfied, Satisfied]
+        );
+        assert_eq!(
+            verdicts("G (req -> X grant)", &[&["req"], &["grant"], &["req"], &[]]),
+            vec![Inconclusive, Inconclusive, Inconclusive, Violated]
+        );
+        assert_eq!(
+            verdicts("G (req -> F grant)", &[&["req"], &[], &["grant"]]),
+            vec![Inconclusive; 3]
+        );
+        assert_eq!(verdicts("a & !a", &[&["a"]]), vec![Violated]);
+        assert_eq!(verdicts("X a | !X a", &[&[]]), vec![Satisfied]);
+        assert_eq!(
+            verdicts("a U b", &[&["a"], &["c"], &["b"]]),
+            vec![Inconclusive, Violated, Violated]
+        );
+    }
+
+    #[test]
+    fn test_initial_verdict() {
+        assert_eq!(
+            Monitor::new("F a").unwrap().verdict(),
+            Verdict::Inconclusive
+        );
+        assert_eq!(
+            Monitor::new("G a & F !a").unwrap().verdict(),
+            Verdict::Violated
+        );
+        assert_eq!(
+            Monitor::new("F a | G !a").unwrap().verdict(),
+            Verdict::Satisfied
+        );
+
+        let mut monitor = Monitor::new("G !a").unwrap();
+        assert_eq!(monitor.step_valuation(1), Verdict::Violated);
+        monitor.reset();
+        assert_eq!(monitor.step_valuation(0), Verdict::Inconclusive);
+    }
+
+    #[test]
+    fn test_unused_propositions() {
+        use crate::{ap, next};
+
+        let tautology = ap("b") | next(ap("a").implies(ap("a")));
+        let mut monitor = Monitor::from_formula(&tautology);
+        assert_eq!(monitor.step(&["a", "b"]), Verdict::Satisfied);
+
+        // `b` is named, but only `a` occurs.
+        let formula = Formula::from_parts(
+            LTLFormula::always(LTLFormula::ap(0)),
+            HashMap::from([("a".to_string(), 0), ("b".to_string(), 1)]),
+        );
+        let mut monitor = Monitor::from_formula(&formula);
+        assert_eq!(monitor.step(&["a", "b"]), Verdict::Inconclusive);
+        assert_eq!(monitor.step(&["a"]), Verdict::Inconclusive);
+        assert_eq!(monitor.step(&["b"]), Verdict::Violated);
+    }
+}
@@ -1,16 +1,41 @@
 use crate::{ModelCheckingError, ModelCheckingErrorKind};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fmt::{Display, Formatter};
 
-mod lexer;
+pub(crate) mod lexer;
 mod parser;
 pub mod parsing_error;
+pub mod printer;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+use parsing_error::{ErrorKind, ParsingError};
+
+/// Syntax of formulas, so that they can be exchanged with other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dialect {
+    /// Syntax of this crate, which also accepts the Unicode and ASCII-arrow spellings.
+    #[default]
+    Native,
+    /// Syntax of Spot and LTL2BA: `O` is no operator, `[]`, `<>` and `V` are accepted. Binary
+    /// temporal operators bind stronger than `&` and group to the right.
+    Spot,
+    /// Syntax of NuSMV `LTLSPEC`s: temporal operators and `TRUE`/`FALSE` are keywords, there is
+    /// no weak until. Binary temporal operators bind stronger than `&`.
+    NuSMV,
+}
+
+/// LTL formula in the core syntax the checker works on. Propositions are numbered, the other
+/// operators are expressed by the constructors of the same name. See [`crate::Formula`] for
+/// building formulas with named propositions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LTLFormula {
     AP(u8),
     Not(Box<LTLFormula>),
     And(Box<LTLFormula>, Box<LTLFormula>),
     Next(Box<LTLFormula>),
+    /// Until, which is weak if the flag is set.
     Until(bool, Box<LTLFormula>, Box<LTLFormula>),
 }
 
@@ -28,27 +53,336 @@ impl LTLFormula {
         Self::And(Box::new(phi1), Box::new(phi2))
     }
 
+    /// `phi1 | phi2`, expressed as `!(!phi1 & !phi2)`.
+    pub fn or(phi1: LTLFormula, phi2: LTLFormula) -> Self {
+        Self::not(Self::and(Self::not(phi1), Self::not(phi2)))
+    }
+
+    /// `phi1 -> phi2`, expressed as `!(phi1 & !phi2)`.
+    pub fn implies(phi1: LTLFormula, phi2: LTLFormula) -> Self {
+        Self::not(Self::and(phi1, Self::not(phi2)))
+    }
+
+    /// `phi1 <-> phi2`, expressed as `(phi1 -> phi2) & (phi2 -> phi1)`.
+    pub fn equivalent(phi1: LTLFormula, phi2: LTLFormula) -> Self {
+        Self::and(
+            Self::implies(phi1.clone(), phi2.clone()),
+            Self::implies(phi2, phi1),
+        )
+    }
+
     pub fn next(phi: LTLFormula) -> Self {
         Self::Next(Box::new(phi))
     }
 
+    /// `WX phi`, expressed as `!X !phi`. It differs from `X phi` only on finite traces, where
+    /// it holds in the last state.
+    pub fn weak_next(phi: LTLFormula) -> Self {
+        Self::not(Self::next(Self::not(phi)))
+    }
+
     pub fn until(phi1: LTLFormula, phi2: LTLFormula, weak: bool) -> Self {
         Self::Until(weak, Box::new(phi1), Box::new(phi2))
     }
+
+    /// `phi1 R phi2`, expressed as `!(!phi1 U !phi2)`.
+    pub fn release(phi1: LTLFormula, phi2: LTLFormula) -> Self {
+        Self::not(Self::until(Self::not(phi1), Self::not(phi2), false))
+    }
+
+    /// `true`, expressed as `!(p0 & !p0)`.
+    pub fn truth() -> Self {
+        Self::not(Self::falsity())
+    }
+
+    /// `false`, expressed as `p0 & !p0`.
+    pub fn falsity() -> Self {
+        Self::and(Self::ap(0), Self::not(Self::ap(0)))
+    }
+
+    /// `F phi`, expressed as `true U phi`.
+    pub fn eventually(phi: LTLFormula) -> Self {
+        Self::until(Self::truth(), phi, false)
+    }
+
+    /// `G phi`, expressed as `!F !phi`.
+    pub fn always(phi: LTLFormula) -> Self {
+        Self::not(Self::eventually(Self::not(phi)))
+    }
+
+    /// `X[n] phi`, expressed as `n` nested `X`.
+    pub fn next_n(phi: LTLFormula, n: u32) -> Self {
+        (0..n).fold(phi, |phi, _| Self::next(phi))
+    }
+
+    /// `F[a,b] phi`, which holds if `phi` holds in one of the steps `a` to `b`, expressed as
+    /// `X[a] (phi | X (phi | ...))`.
+    pub fn eventually_within(phi: LTLFormula, a: u32, b: u32) -> Self {
+        let unrolled = (a..b).fold(phi.clone(), |rest, _| {
+            Self::or(phi.clone(), Self::next(rest))
+        });
+        Self::next_n(unrolled, a)
+    }
+
+    /// `G[a,b] phi`, which holds if `phi` holds in all steps `a` to `b`, expressed as
+    /// `X[a] (phi & X (phi & ...))`.
+    pub fn always_within(phi: LTLFormula, a: u32, b: u32) -> Self {
+        let unrolled = (a..b).fold(phi.clone(), |rest, _| {
+            Self::and(phi.clone(), Self::next(rest))
+        });
+        Self::next_n(unrolled, a)
+    }
+
+    /// `phi1 U[a,b] phi2`, which holds if `phi2` holds in one of the steps `a` to `b` and
+    /// `phi1` holds in all steps before, expressed as
+    /// `phi1 & X (... (phi2 | phi1 & X (phi2 | ...)))`.
+    pub fn until_within(phi1: LTLFormula, phi2: LTLFormula, a: u32, b: u32) -> Self {
+        let unrolled = (a..b).fold(phi2.clone(), |rest, _| {
+            Self::or(phi2.clone(), Self::and(phi1.clone(), Self::next(rest)))
+        });
+        (0..a).fold(unrolled, |rest, _| {
+            Self::and(phi1.clone(), Self::next(rest))
+        })
+    }
+
+    /// Renumbers the propositions of the formula. The encoding of `false` keeps using `p0`.
+    pub fn map_aps(&self, f: &impl Fn(u8) -> u8) -> LTLFormula {
+        match self {
+            _ if *self == LTLFormula::falsity() => self.clone(),
+            LTLFormula::AP(ap) => LTLFormula::ap(f(*ap)),
+            LTLFormula::Not(phi) => LTLFormula::not(phi.map_aps(f)),
+            LTLFormula::And(phi1, phi2) => LTLFormula::and(phi1.map_aps(f), phi2.map_aps(f)),
+            LTLFormula::Next(phi) => LTLFormula::next(phi.map_aps(f)),
+            LTLFormula::Until(weak, phi1, phi2) => {
+                LTLFormula::until(phi1.map_aps(f), phi2.map_aps(f), *weak)
+            }
+        }
+    }
+
+    /// Numbers of the propositions that occur in the formula.
+    pub fn aps(&self) -> BTreeSet<u8> {
+        match self {
+            LTLFormula::AP(ap) => BTreeSet::from([*ap]),
+            LTLFormula::Not(phi) | LTLFormula::Next(phi) => phi.aps(),
+            LTLFormula::And(phi1, phi2) | LTLFormula::Until(_, phi1, phi2) => {
+                &phi1.aps() | &phi2.aps()
+            }
+        }
+    }
+
+    /// Largest number of a proposition in the formula.
+    pub fn max_ap(&self) -> u8 {
+        match self {
+            LTLFormula::AP(ap) => *ap,
+            LTLFormula::Not(phi) | LTLFormula::Next(phi) => phi.max_ap(),
+            LTLFormula::And(phi1, phi2) | LTLFormula::Until(_, phi1, phi2) => {
+                phi1.max_ap().max(phi2.max_ap())
+            }
+        }
+    }
+
+    /// Prints the formula in the syntax of this crate, naming its propositions as in `ap_map`.
+    /// Parsing the result gives the formula back. See [`printer::print`].
+    pub fn to_string_with(&self, ap_map: &HashMap<String, u8>) -> String {
+        printer::print(self, ap_map, Dialect::Native).expect("every name can be quoted")
+    }
+
+    pub fn contains_next(&self) -> bool {
+        match self {
+            LTLFormula::AP(_) => false,
+            LTLFormula::Not(phi) => phi.contains_next(),
+            LTLFormula::And(phi1, phi2) | LTLFormula::Until(_, phi1, phi2) => {
+                phi1.contains_next() || phi2.contains_next()
+            }
+            LTLFormula::Next(_) => true,
+        }
+    }
+
+    /// Translates a formula with LTLf semantics into an LTL formula that holds on a finite path
+    /// extended by an infinite suffix in which `alive` is false exactly if the formula holds on
+    /// the finite path. Infinite paths on which `alive` always holds are left unchanged.
+    pub fn to_finite_semantics(&self, alive: u8) -> LTLFormula {
+        LTLFormula::and(LTLFormula::ap(alive), self.to_finite_semantics_rec(alive))
+    }
+
+    fn to_finite_semantics_rec(&self, alive: u8) -> LTLFormula {
+        match self {
+            LTLFormula::AP(ap) => LTLFormula::ap(*ap),
+            LTLFormula::Not(phi) => LTLFormula::not(phi.to_finite_semantics_rec(alive)),
+            LTLFormula::And(phi1, phi2) => LTLFormula::and(
+                phi1.to_finite_semantics_rec(alive),
+                phi2.to_finite_semantics_rec(alive),
+            ),
+            LTLFormula::Next(phi) => LTLFormula::next(LTLFormula::and(
+                LTLFormula::ap(alive),
+                phi.to_finite_semantics_rec(alive),
+            )),
+            LTLFormula::Until(weak, phi1, phi2) => {
+                let phi1 = phi1.to_finite_semantics_rec(alive);
+                // The weak until may also hold because the path ends: (phi1 | !alive) W ...
+                let phi1 = if *weak {
+                    LTLFormula::not(LTLFormula::and(
+                        LTLFormula::not(phi1),
+                        LTLFormula::ap(alive),
+                    ))
+                } else {
+                    phi1
+                };
+                LTLFormula::until(
+                    phi1,
+                    LTLFormula::and(LTLFormula::ap(alive), phi2.to_finite_semantics_rec(alive)),
+                    *weak,
+                )
+            }
+        }
+    }
+}
+
+/// Propositions are printed as `p` followed by their number, use
+/// [`LTLFormula::to_string_with`] to print their names.
+impl Display for LTLFormula {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with(&HashMap::new()))
+    }
+}
+
+pub fn parse<Id>(text: &str) -> Result<(LTLFormula, HashMap<String, u8>), ModelCheckingError<Id>> {
+    parse_in(text, Dialect::Native)
 }
 
-pub fn parse(text: &str) -> Result<(LTLFormula, HashMap<String, u8>), ModelCheckingError> {
-    let (tokens, ap_map) = lexer::lexer(text)
+/// Like [`parse`], but reads `text` in the syntax of `dialect`.
+pub fn parse_in<Id>(
+    text: &str,
+    dialect: Dialect,
+) -> Result<(LTLFormula, HashMap<String, u8>), ModelCheckingError<Id>> {
+    let (tokens, ap_map) = lexer::lexer(text, dialect)
         .map_err(|err| ModelCheckingError::new(ModelCheckingErrorKind::FormulaSytaxError(err)))?;
     if ap_map.is_empty() {
         return Err(ModelCheckingError::new(
             ModelCheckingErrorKind::FormulaNoAPs,
         ));
     }
-    let ast = parser::parser(tokens)
+    let ast = parser::parser(text, tokens, dialect)
         .map_err(|err| ModelCheckingError::new(ModelCheckingErrorKind::FormulaSytaxError(err)))?;
     Ok((ast, ap_map))
 }
 
+/// Parses as much of `text` as possible and returns every syntax error, sorted by position,
+/// together with the formula that could be recovered. See [`parser::recovering_parser`].
+pub fn parse_with_diagnostics(
+    text: &str,
+) -> (Option<LTLFormula>, HashMap<String, u8>, Vec<ParsingError>) {
+    let (tokens, ap_map, mut errors) = lexer::recovering_lexer(text, Dialect::Native);
+    let (ast, parser_errors) = parser::recovering_parser(text, tokens, Dialect::Native);
+    errors.extend(parser_errors);
+    errors.sort_by_key(|error| error.at());
+    if ap_map.is_empty() && ast.is_some() {
+        errors.push(ParsingError::new(ErrorKind::NoAPs, text, None));
+    }
+    (ast, ap_map, errors)
+}
+
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics() {
+        let (ast, ap_map, errors) = parse_with_diagnostics("G(a ? b) & ( | c");
+        assert!(ast.is_some());
+        assert_eq!(ap_map.len(), 3);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.kind().clone())
+                .collect::<Vec<ErrorKind>>(),
+            vec![
+                ErrorKind::UnexpectedToken,
+                ErrorKind::AdjacentOperands,
+                ErrorKind::UnmatchedOpenParenthesis,
+                ErrorKind::MissingLeftOperand
+            ]
+        );
+        let (ast, _, errors) = parse_with_diagnostics("G 1");
+        assert!(ast.is_some());
+        assert_eq!(errors[0].kind(), &ErrorKind::NoAPs);
+        let (_, _, errors) = parse_with_diagnostics("Fire & a");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind(), &ErrorKind::OperatorInName);
+        assert_eq!(
+            errors[0].to_string().lines().next(),
+            Some("'Fire' begins with an operator, write \"Fire\" for the proposition or separate the operator at position 0")
+        );
+    }
+
+    #[test]
+    fn test_dialects() {
+        let native = parse::<u64>("G(a -> F b) & (c R !d)").unwrap();
+        assert_eq!(
+            parse_in::<u64>("[](a -> <>b) && (c V ~d)", Dialect::Spot).unwrap(),
+            native
+        );
+        assert_eq!(
+            parse_in::<u64>("G (a -> F b) & (c V !d)", Dialect::NuSMV).unwrap(),
+            native
+        );
+
+        // `O` and lowercase keywords are names in the other dialects.
+        let (_, ap_map) = parse_in::<u64>("O & true", Dialect::Spot).unwrap();
+        assert!(ap_map.contains_key("O"));
+        let (_, ap_map) = parse_in::<u64>("Ga & p.state & true", Dialect::NuSMV).unwrap();
+        assert_eq!(ap_map.len(), 3);
+        assert!(parse_in::<u64>("a W b", Dialect::NuSMV).is_err());
+        assert!(parse_in::<u64>("\"a\"", Dialect::NuSMV).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let (ltl, ap_map) = parse::<u64>("G(req -> F grant) & (\"x>3\" R !(req | grant))").unwrap();
+        let printed = ltl.to_string_with(&ap_map);
+        assert_eq!(printed, "G (req -> F grant) & (\"x>3\" R !(req | grant))");
+        assert_eq!(parse::<u64>(&printed).unwrap().0, ltl);
+        assert_eq!(ltl.to_string(), "G (p0 -> F p1) & (p2 R !(p0 | p1))");
+    }
+
+    #[test]
+    fn test_bounded() {
+        for (bounded, unrolled) in [
+            ("F[0,3] grant", "grant | X (grant | X (grant | X grant))"),
+            ("G[2,3] safe", "X X (safe & X safe)"),
+            ("X[3] p & X[0] q", "X X X p & q"),
+            ("a U[1,2] b", "a & X (b | a & X b)"),
+            ("F[2,2] a U[0,1] b", "b | X X a & X b"),
+        ] {
+            let print = |text| {
+                let (ltl, ap_map) = parse::<u64>(text).unwrap();
+                ltl.to_string_with(&ap_map)
+            };
+            assert_eq!(print(bounded), print(unrolled), "{bounded}");
+        }
+
+        // Bounded operators may not unroll to more than MAX_BOUND nested X in total.
+        for formula in [
+            "F[0,16] a",
+            "G(a -> X[12] b)",
+            "X X F[0,7] a",
+            "F[0,5] G[0,4] a",
+        ] {
+            let error = parse::<u64>(formula).unwrap_err();
+            let ModelCheckingErrorKind::FormulaSytaxError(error) = error.kind() else {
+                panic!("{formula}")
+            };
+            assert_eq!(error.kind(), &ErrorKind::BoundTooLarge, "{formula}");
+        }
+        assert!(parse::<u64>("F[0,8] a & X[8] b").is_ok());
+        assert!(parse::<u64>("X X X X X X X X X a").is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let (ast, _) = parse::<u64>("G(!a | X b) & (c W !a)").unwrap();
+        let json = serde_json::to_string(&ast).unwrap();
+        assert_eq!(serde_json::from_str::<LTLFormula>(&json).unwrap(), ast);
+    }
+}
@@ -1,33 +1,44 @@
-use crate::parsing::parsing_error::{ErrorKind, ParsingError};
+use crate::parsing::parsing_error::{ErrorKind, ParsingError, Span};
+use crate::parsing::Dialect;
 use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LTLTokenUnaryPrefix {
     Next,
     Not,
     Future,
     Generally,
+    /// `WX`, which unlike `X` also holds in the last state of a finite trace.
+    WeakNext,
+    /// `X[n]`
+    BoundedNext(u32),
+    /// `F[a,b]`
+    BoundedFuture(u32, u32),
+    /// `G[a,b]`
+    BoundedGenerally(u32, u32),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LTLTokenBinaryInfix {
     And,
     Or,
-    #[allow(dead_code)]
     Implies,
+    Equivalence,
     Until,
     WeakUntil,
     Release,
+    /// `U[a,b]`
+    BoundedUntil(u32, u32),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LTLTokenAtomic {
     AP(u8),
     True,
     False,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LTLToken {
     BinaryInfix(LTLTokenBinaryInfix),
     UnaryPrefix(LTLTokenUnaryPrefix),
@@ -36,81 +47,386 @@ pub enum LTLToken {
     CloseParenthesis,
 }
 
-pub fn lexer(text: &str) -> Result<(Vec<LTLToken>, HashMap<String, u8>), ParsingError> {
-    #[derive(PartialEq)]
-    enum State {
-        None,
-        InAP,
+pub type SpannedToken = (LTLToken, Span);
+
+pub type LexerOutput = (Vec<SpannedToken>, HashMap<String, u8>);
+
+/// Largest number of nested `X` in a formula with bounded operators, which are unrolled into
+/// nested `X`. Every level of nesting multiplies the size of the automaton, so this counts the
+/// unrolled bounds together with the `X` around and inside them.
+pub const MAX_BOUND: u32 = 8;
+
+/// Operators of [`Dialect::Native`], with alternative spellings from other tools and papers.
+/// Longer spellings have to come before their prefixes.
+const NATIVE_OPERATORS: &[(&str, LTLToken)] = &[
+    (
+        "<->",
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence),
+    ),
+    (
+        "<=>",
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence),
+    ),
+    ("->", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("=>", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("&&", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("||", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("(", LTLToken::OpenParenthesis),
+    (")", LTLToken::CloseParenthesis),
+    ("!", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("~", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("¬", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("&", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("∧", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("|", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("∨", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("→", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("↔", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence)),
+    ("U", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until)),
+    ("R", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Release)),
+    ("WX", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::WeakNext)),
+    ("W", LTLToken::BinaryInfix(LTLTokenBinaryInfix::WeakUntil)),
+    ("X", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next)),
+    ("O", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next)),
+    ("○", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next)),
+    ("G", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally)),
+    ("□", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally)),
+    ("F", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future)),
+    ("◇", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future)),
+    ("1", LTLToken::Atomic(LTLTokenAtomic::True)),
+    ("0", LTLToken::Atomic(LTLTokenAtomic::False)),
+];
+
+const SPOT_OPERATORS: &[(&str, LTLToken)] = &[
+    (
+        "<->",
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence),
+    ),
+    (
+        "<=>",
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence),
+    ),
+    ("->", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("=>", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("&&", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("||", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("/\\", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("\\/", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("[]", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally)),
+    ("<>", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future)),
+    ("(", LTLToken::OpenParenthesis),
+    (")", LTLToken::CloseParenthesis),
+    ("!", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("~", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("&", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("|", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+    ("U", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until)),
+    ("R", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Release)),
+    ("V", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Release)),
+    ("W", LTLToken::BinaryInfix(LTLTokenBinaryInfix::WeakUntil)),
+    ("X", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next)),
+    ("G", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally)),
+    ("F", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future)),
+    ("1", LTLToken::Atomic(LTLTokenAtomic::True)),
+    ("0", LTLToken::Atomic(LTLTokenAtomic::False)),
+];
+
+/// NuSMV has no single-letter operators, its temporal operators are keywords.
+const NUSMV_OPERATORS: &[(&str, LTLToken)] = &[
+    (
+        "<->",
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Equivalence),
+    ),
+    ("->", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Implies)),
+    ("(", LTLToken::OpenParenthesis),
+    (")", LTLToken::CloseParenthesis),
+    ("!", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not)),
+    ("&", LTLToken::BinaryInfix(LTLTokenBinaryInfix::And)),
+    ("|", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or)),
+];
+
+const KEYWORDS: &[(&str, LTLToken)] = &[
+    ("true", LTLToken::Atomic(LTLTokenAtomic::True)),
+    ("false", LTLToken::Atomic(LTLTokenAtomic::False)),
+];
+
+const NUSMV_KEYWORDS: &[(&str, LTLToken)] = &[
+    ("X", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next)),
+    ("F", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future)),
+    ("G", LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally)),
+    ("U", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until)),
+    ("V", LTLToken::BinaryInfix(LTLTokenBinaryInfix::Release)),
+    ("TRUE", LTLToken::Atomic(LTLTokenAtomic::True)),
+    ("FALSE", LTLToken::Atomic(LTLTokenAtomic::False)),
+];
+
+fn operators(dialect: Dialect) -> &'static [(&'static str, LTLToken)] {
+    match dialect {
+        Dialect::Native => NATIVE_OPERATORS,
+        Dialect::Spot => SPOT_OPERATORS,
+        Dialect::NuSMV => NUSMV_OPERATORS,
     }
+}
+
+/// Names that are read as operators or constants instead of propositions.
+pub fn keywords(dialect: Dialect) -> &'static [(&'static str, LTLToken)] {
+    match dialect {
+        Dialect::Native | Dialect::Spot => KEYWORDS,
+        Dialect::NuSMV => NUSMV_KEYWORDS,
+    }
+}
 
-    let mut tokens = Vec::<LTLToken>::new();
+pub fn lexer(text: &str, dialect: Dialect) -> Result<LexerOutput, ParsingError> {
+    let (tokens, aps, mut errors) = recovering_lexer(text, dialect);
+    if errors.is_empty() {
+        Ok((tokens, aps))
+    } else {
+        Err(errors.remove(0))
+    }
+}
+
+/// Like [`lexer`], but skips characters it cannot read and reports all of them.
+pub fn recovering_lexer(
+    text: &str,
+    dialect: Dialect,
+) -> (Vec<SpannedToken>, HashMap<String, u8>, Vec<ParsingError>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::<SpannedToken>::new();
     let mut aps = HashMap::<String, u8>::new();
-    let mut state = State::None;
-    let mut ap_name = String::new();
+    let mut errors = Vec::<ParsingError>::new();
+    let mut i = 0;
 
-    for (i, c) in text.chars().enumerate() {
-        if state == State::InAP {
-            if c.is_alphanumeric() || c == '_' {
-                ap_name.push(c);
-                continue;
-            } else {
-                let ap_option = aps.get(&ap_name);
-                let val: u8;
-                if ap_option.is_some() {
-                    val = *ap_option.unwrap();
-                } else {
-                    val = aps.len() as u8;
-                    aps.insert(ap_name, val);
-                }
+    'chars: while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
 
-                tokens.push(LTLToken::Atomic(LTLTokenAtomic::AP(val)));
-                ap_name = String::new();
-                state = State::None;
+        if c == '"' && dialect != Dialect::NuSMV {
+            let start = i;
+            let mut name = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    Some('"') => break,
+                    Some('\\') if matches!(chars.get(i + 1), Some('"' | '\\')) => {
+                        name.push(chars[i + 1]);
+                        i += 2;
+                    }
+                    Some(c) => {
+                        name.push(*c);
+                        i += 1;
+                    }
+                    None => {
+                        errors.push(ParsingError::new(
+                            ErrorKind::UnterminatedQuote,
+                            text,
+                            Some(Span::new(start, start + 1)),
+                        ));
+                        break 'chars;
+                    }
+                }
             }
+            i += 1;
+            let next = aps.len() as u8;
+            let ap = LTLTokenAtomic::AP(*aps.entry(name).or_insert(next));
+            tokens.push((LTLToken::Atomic(ap), Span::new(start, i)));
+            continue;
         }
 
-        if c.is_whitespace() {
+        if starts_name(c, dialect) {
+            let start = i;
+            i = identifier_end(&chars, i, dialect);
+            let name: String = chars[start..i].iter().collect();
+            let token = match keywords(dialect)
+                .iter()
+                .find(|(keyword, _)| *keyword == name)
+            {
+                Some((_, token)) => token.clone(),
+                None => {
+                    let next = aps.len() as u8;
+                    LTLToken::Atomic(LTLTokenAtomic::AP(*aps.entry(name).or_insert(next)))
+                }
+            };
+            tokens.push((token, Span::new(start, i)));
             continue;
         }
 
-        if c.is_ascii_lowercase() {
-            state = State::InAP;
-            ap_name.push(c);
-        } else {
-            let token = match c {
-                '(' => LTLToken::OpenParenthesis,
-                ')' => LTLToken::CloseParenthesis,
-                '!' => LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Not),
-                '&' => LTLToken::BinaryInfix(LTLTokenBinaryInfix::And),
-                '|' => LTLToken::BinaryInfix(LTLTokenBinaryInfix::Or),
-                'U' => LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until),
-                'X' | 'O' => LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next),
-                'R' => LTLToken::BinaryInfix(LTLTokenBinaryInfix::Release),
-                'W' => LTLToken::BinaryInfix(LTLTokenBinaryInfix::WeakUntil),
-                'G' => LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally),
-                'F' => LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future),
-                '0' => LTLToken::Atomic(LTLTokenAtomic::False),
-                '1' => LTLToken::Atomic(LTLTokenAtomic::True),
-                _ => return Err(ParsingError::new(ErrorKind::UnexpectedToken, text, Some(i))),
-            };
+        // After an operand, `WX` is read as `W X`, as before there was a weak next.
+        let after_operand = matches!(
+            tokens.last(),
+            Some((LTLToken::Atomic(_) | LTLToken::CloseParenthesis, _))
+        );
+        for (spelling, token) in operators(dialect) {
+            if after_operand && *token == LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::WeakNext) {
+                continue;
+            }
+            let length = spelling.chars().count();
+            if chars[i..].iter().take(length).copied().eq(spelling.chars()) {
+                let start = i;
+                i += length;
+                // `Grant` is read as `G rant`, which is hardly what was meant.
+                let is_letter = spelling.chars().all(char::is_alphabetic);
+                if is_letter && chars.get(i).is_some_and(|c| c.is_lowercase()) {
+                    let end = identifier_end(&chars, i, dialect);
+                    if end - i > 1 {
+                        errors.push(ParsingError::new(
+                            ErrorKind::OperatorInName,
+                            text,
+                            Some(Span::new(start, end)),
+                        ));
+                    }
+                }
+                match bounds(&chars, i).filter(|_| is_boundable(token)) {
+                    Some((numbers, end)) => {
+                        let span = Span::new(start, end);
+                        i = end;
+                        match bounded(token, numbers) {
+                            Ok(token) => tokens.push((token, span)),
+                            Err(kind) => {
+                                errors.push(ParsingError::new(kind, text, Some(span)));
+                                tokens.push((token.clone(), span));
+                            }
+                        }
+                    }
+                    None => tokens.push((token.clone(), Span::new(start, i))),
+                }
+                continue 'chars;
+            }
+        }
+
+        errors.push(ParsingError::new(
+            ErrorKind::UnexpectedToken,
+            text,
+            Some(Span::new(i, i + 1)),
+        ));
+        i += 1;
+    }
+
+    (tokens, aps, errors)
+}
+
+fn is_boundable(token: &LTLToken) -> bool {
+    matches!(
+        token,
+        LTLToken::UnaryPrefix(
+            LTLTokenUnaryPrefix::Next
+                | LTLTokenUnaryPrefix::Future
+                | LTLTokenUnaryPrefix::Generally
+        ) | LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until)
+    )
+}
 
-            tokens.push(token);
+/// Reads bounds such as `[3]` or `[2,5]` starting at `start` and returns them together with
+/// the offset after the closing bracket, or the offset to continue at if the bounds are
+/// malformed. Like in Spot, `..` and `:` separate bounds as well. Returns `None` if no number
+/// in brackets follows, so that Spot's `[]` stays an operator.
+fn bounds(chars: &[char], start: usize) -> Option<(Option<Vec<u32>>, usize)> {
+    let skip_whitespace = |mut i: usize| {
+        while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+            i += 1;
+        }
+        i
+    };
+    let is_digit = |i: usize| chars.get(i).is_some_and(|c| c.is_ascii_digit());
+    if chars.get(start) != Some(&'[') || !is_digit(skip_whitespace(start + 1)) {
+        return None;
+    }
+    let mut numbers = vec![];
+    let mut i = start + 1;
+    loop {
+        i = skip_whitespace(i);
+        let digits = i;
+        while is_digit(i) {
+            i += 1;
+        }
+        if digits == i {
+            return Some((None, i));
+        }
+        let number: String = chars[digits..i].iter().collect();
+        // Numbers too large for u32 are still too large as bounds.
+        numbers.push(number.parse().unwrap_or(u32::MAX));
+        i = skip_whitespace(i);
+        match chars.get(i) {
+            Some(']') => return Some((Some(numbers), i + 1)),
+            Some(',' | ':') => i += 1,
+            Some('.') if chars.get(i + 1) == Some(&'.') => i += 2,
+            _ => return Some((None, i)),
         }
     }
+}
 
-    if state == State::InAP {
-        let ap_option = aps.get(&ap_name);
-        let val: u8;
-        if ap_option.is_some() {
-            val = *ap_option.unwrap();
-        } else {
-            val = aps.len() as u8;
-            aps.insert(ap_name, val);
+/// The bounded variant of the boundable `token`. `X` takes one bound, the other operators an
+/// interval.
+fn bounded(token: &LTLToken, numbers: Option<Vec<u32>>) -> Result<LTLToken, ErrorKind> {
+    let numbers = numbers.ok_or(ErrorKind::InvalidBound)?;
+    let interval = || match numbers[..] {
+        [a, b] if a <= b => Ok((a, b)),
+        _ => Err(ErrorKind::InvalidBound),
+    };
+    let token = match token {
+        LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Next) => match numbers[..] {
+            [n] => LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::BoundedNext(n)),
+            _ => return Err(ErrorKind::InvalidBound),
+        },
+        LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Future) => {
+            let (a, b) = interval()?;
+            LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::BoundedFuture(a, b))
+        }
+        LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::Generally) => {
+            let (a, b) = interval()?;
+            LTLToken::UnaryPrefix(LTLTokenUnaryPrefix::BoundedGenerally(a, b))
         }
-        tokens.push(LTLToken::Atomic(LTLTokenAtomic::AP(val)));
+        LTLToken::BinaryInfix(LTLTokenBinaryInfix::Until) => {
+            let (a, b) = interval()?;
+            LTLToken::BinaryInfix(LTLTokenBinaryInfix::BoundedUntil(a, b))
+        }
+        _ => unreachable!("only boundable tokens get bounds"),
+    };
+    if numbers.iter().any(|n| *n > MAX_BOUND) {
+        return Err(ErrorKind::BoundTooLarge);
     }
+    Ok(token)
+}
+
+/// Whether `c` starts a name. Uppercase letters that spell an operator start the operator
+/// instead, as in `GFa`.
+pub fn starts_name(c: char, dialect: Dialect) -> bool {
+    let is_operator = || {
+        operators(dialect)
+            .iter()
+            .any(|(spelling, _)| spelling.chars().eq([c]))
+    };
+    (c.is_alphabetic() && !(c.is_uppercase() && is_operator())) || c == '_'
+}
 
-    Ok((tokens, aps))
+/// End of the identifier starting at `start`. Identifiers consist of letters, digits and `_`,
+/// and may be divided into parts by `.` or `::`, as in `door.open` or `robot::arm`. NuSMV also
+/// allows `$` and `#`.
+pub fn identifier_end(chars: &[char], start: usize, dialect: Dialect) -> usize {
+    let is_part = |c: Option<&char>| {
+        c.is_some_and(|c| {
+            c.is_alphanumeric()
+                || *c == '_'
+                || (dialect == Dialect::NuSMV && (*c == '$' || *c == '#'))
+        })
+    };
+    let mut i = start;
+    loop {
+        if is_part(chars.get(i)) {
+            i += 1;
+        } else if chars.get(i) == Some(&'.') && is_part(chars.get(i + 1)) {
+            i += 2;
+        } else if chars.get(i) == Some(&':')
+            && chars.get(i + 1) == Some(&':')
+            && is_part(chars.get(i + 2))
+        {
+            i += 3;
+        } else {
+            return i;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -121,10 +437,27 @@ mod tests {
     use LTLTokenBinaryInfix as B;
     use LTLTokenUnaryPrefix as U;
 
+    fn lexer(text: &str) -> Result<LexerOutput, ParsingError> {
+        super::lexer(text, Dialect::Native)
+    }
+
+    fn recovering_lexer(text: &str) -> (Vec<SpannedToken>, HashMap<String, u8>, Vec<ParsingError>) {
+        super::recovering_lexer(text, Dialect::Native)
+    }
+
+    fn tokens(text: &str) -> Vec<LTLToken> {
+        lexer(text)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
     #[test]
     fn test_operators() {
         assert_eq!(
-            lexer("a!Ub&)Xa(").unwrap().0,
+            tokens("a!Ub&)Xa("),
             vec![
                 L::Atomic(A::AP(0)),
                 L::UnaryPrefix(U::Not),
@@ -139,10 +472,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_alternative_spellings() {
+        assert_eq!(
+            tokens("a -> b <-> ~c => d && e || true <=> false"),
+            tokens("a → b ↔ ¬c → d ∧ e ∨ 1 ↔ 0")
+        );
+        assert_eq!(
+            tokens("□◇○a"),
+            vec![
+                L::UnaryPrefix(U::Generally),
+                L::UnaryPrefix(U::Future),
+                L::UnaryPrefix(U::Next),
+                L::Atomic(A::AP(0))
+            ]
+        );
+        assert_eq!(
+            tokens("a->b<->c"),
+            vec![
+                L::Atomic(A::AP(0)),
+                L::BinaryInfix(B::Implies),
+                L::Atomic(A::AP(1)),
+                L::BinaryInfix(B::Equivalence),
+                L::Atomic(A::AP(2))
+            ]
+        );
+        assert_eq!(
+            tokens("WX a W b"),
+            vec![
+                L::UnaryPrefix(U::WeakNext),
+                L::Atomic(A::AP(0)),
+                L::BinaryInfix(B::WeakUntil),
+                L::Atomic(A::AP(1))
+            ]
+        );
+        assert_eq!(
+            tokens("a WX b | (c) WX WX d"),
+            vec![
+                L::Atomic(A::AP(0)),
+                L::BinaryInfix(B::WeakUntil),
+                L::UnaryPrefix(U::Next),
+                L::Atomic(A::AP(1)),
+                L::BinaryInfix(B::Or),
+                L::OpenParenthesis,
+                L::Atomic(A::AP(2)),
+                L::CloseParenthesis,
+                L::BinaryInfix(B::WeakUntil),
+                L::UnaryPrefix(U::Next),
+                L::UnaryPrefix(U::WeakNext),
+                L::Atomic(A::AP(3))
+            ]
+        );
+        let (_, aps) = lexer("true & trueish").unwrap();
+        assert_eq!(aps.len(), 1);
+        assert_eq!(lexer("a <- b").unwrap_err().span(), Some(&Span::new(2, 3)));
+        assert_eq!(lexer("a → b").unwrap().0[2].1, Span::new(4, 5));
+    }
+
     #[test]
     fn test_long_variables() {
         assert_eq!(
-            lexer("aUntilB U a_until_b aUntilB").unwrap().0,
+            tokens("aUntilB U a_until_b aUntilB"),
             vec![
                 L::Atomic(A::AP(0)),
                 L::BinaryInfix(B::Until),
@@ -152,15 +542,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_names() {
+        let (tokens, aps) = lexer(r#"Zahl & door.open U robot::arm_1 & "x>3" & "Ready""#).unwrap();
+        assert_eq!(tokens.len(), 9);
+        for name in ["Zahl", "door.open", "robot::arm_1", "x>3", "Ready"] {
+            assert!(aps.contains_key(name), "{name}");
+        }
+        assert_eq!(tokens[8].1, Span::new(42, 49));
+
+        // Operator letters keep their meaning, quoted keywords are names.
+        let (tokens, aps) = lexer(r#"Ga | "true" | "say \"hi\"""#).unwrap();
+        assert_eq!(tokens[0].0, L::UnaryPrefix(U::Generally));
+        assert_eq!(tokens[3].0, L::Atomic(A::AP(1)));
+        assert!(aps.contains_key("true"));
+        assert!(aps.contains_key(r#"say "hi""#));
+
+        assert_eq!(lexer("a.").unwrap_err().span(), Some(&Span::new(1, 2)));
+        for (text, span) in [("Grant", Span::new(0, 5)), ("a U Xray", Span::new(4, 8))] {
+            let error = lexer(text).unwrap_err();
+            assert_eq!(error.kind(), &ErrorKind::OperatorInName, "{text}");
+            assert_eq!(error.span(), Some(&span));
+        }
+        assert!(lexer("GFa & Xb").is_ok());
+        assert_eq!(
+            lexer(r#"a & "b"#).unwrap_err().kind(),
+            &ErrorKind::UnterminatedQuote
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(
+            tokens("F[0,3] a U[1, 2] X[4] G[ 2..5 ]b"),
+            vec![
+                L::UnaryPrefix(U::BoundedFuture(0, 3)),
+                L::Atomic(A::AP(0)),
+                L::BinaryInfix(B::BoundedUntil(1, 2)),
+                L::UnaryPrefix(U::BoundedNext(4)),
+                L::UnaryPrefix(U::BoundedGenerally(2, 5)),
+                L::Atomic(A::AP(1))
+            ]
+        );
+        assert_eq!(lexer("F[0:3] a").unwrap().0[0].1, Span::new(0, 6));
+        assert_eq!(
+            super::lexer("[]<>[1..2]a", Dialect::Spot).unwrap().0[1].0,
+            L::UnaryPrefix(U::BoundedFuture(1, 2))
+        );
+
+        for (formula, kind) in [
+            ("F[3,1] a", ErrorKind::InvalidBound),
+            ("X[1,2] a", ErrorKind::InvalidBound),
+            ("G[1] a", ErrorKind::InvalidBound),
+            ("F[1,a] a", ErrorKind::InvalidBound),
+            ("F[0,65] a", ErrorKind::BoundTooLarge),
+            ("X[99999999999] a", ErrorKind::BoundTooLarge),
+        ] {
+            assert_eq!(lexer(formula).unwrap_err().kind(), &kind, "{formula}");
+        }
+        // Recovery keeps the operator without its bounds.
+        let (tokens, _, errors) = recovering_lexer("F[3,1] a");
+        assert_eq!(tokens[0], (L::UnaryPrefix(U::Future), Span::new(0, 6)));
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans: Vec<Span> = lexer("ab U (c)")
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|(_, span)| span)
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(0, 2),
+                Span::new(3, 4),
+                Span::new(5, 6),
+                Span::new(6, 7),
+                Span::new(7, 8)
+            ]
+        );
+    }
+
     #[test]
     fn test_invalid_chars() {
         assert_eq!(
-            lexer("Zahl").unwrap_err().kind(),
-            ErrorKind::UnexpectedToken
+            lexer("x>3").unwrap_err().kind(),
+            &ErrorKind::UnexpectedToken
         );
         assert_eq!(
             lexer("a ? b").unwrap_err().kind(),
-            ErrorKind::UnexpectedToken
+            &ErrorKind::UnexpectedToken
+        );
+        assert_eq!(lexer("a ? b").unwrap_err().span(), Some(&Span::new(2, 3)));
+    }
+
+    #[test]
+    fn test_recovery() {
+        let (tokens, aps, errors) = recovering_lexer("a ? b # c");
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(aps.len(), 3);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.at())
+                .collect::<Vec<usize>>(),
+            vec![2, 6]
         );
     }
 }
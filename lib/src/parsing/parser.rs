@@ -1,27 +1,54 @@
-use crate::parsing::lexer::{LTLToken, LTLTokenAtomic, LTLTokenBinaryInfix, LTLTokenUnaryPrefix};
-use crate::parsing::parsing_error::ErrorKind::ShittySyntax;
-use crate::parsing::parsing_error::{ErrorKind, ParsingError};
-use crate::parsing::LTLFormula;
+use crate::parsing::lexer::{
+    LTLToken, LTLTokenAtomic, LTLTokenBinaryInfix, LTLTokenUnaryPrefix, SpannedToken, MAX_BOUND,
+};
+use crate::parsing::parsing_error::{ErrorKind, ParsingError, Span};
+use crate::parsing::{Dialect, LTLFormula};
 
-#[derive(Debug)]
-enum LTLBinaryInfixKind {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LTLBinaryInfixKind {
     And,
     Or,
     Implies,
+    Equivalence,
     Until,
     WeakUntil,
     Release,
+    BoundedUntil(u32, u32),
 }
 
 impl LTLBinaryInfixKind {
-    fn operator_precedence(&self) -> u32 {
-        match self {
-            LTLBinaryInfixKind::And => 800,
-            LTLBinaryInfixKind::Or => 600,
-            LTLBinaryInfixKind::Implies => 400,
-            LTLBinaryInfixKind::Until
-            | LTLBinaryInfixKind::WeakUntil
-            | LTLBinaryInfixKind::Release => 200,
+    /// Operators with a higher precedence bind stronger. Unary operators bind stronger than all
+    /// binary ones. Spot and NuSMV bind the temporal operators stronger than `&`, this crate
+    /// binds them weaker than `<->`.
+    pub fn operator_precedence(&self, dialect: Dialect) -> u32 {
+        match (self, dialect) {
+            (
+                LTLBinaryInfixKind::Until
+                | LTLBinaryInfixKind::WeakUntil
+                | LTLBinaryInfixKind::Release
+                | LTLBinaryInfixKind::BoundedUntil(..),
+                Dialect::Spot | Dialect::NuSMV,
+            ) => 900,
+            (LTLBinaryInfixKind::And, _) => 800,
+            (LTLBinaryInfixKind::Or, _) => 600,
+            (LTLBinaryInfixKind::Equivalence, Dialect::NuSMV) => 500,
+            (LTLBinaryInfixKind::Implies, _) => 400,
+            (LTLBinaryInfixKind::Equivalence, Dialect::Spot) => 400,
+            (LTLBinaryInfixKind::Equivalence, Dialect::Native) => 300,
+            (
+                LTLBinaryInfixKind::Until
+                | LTLBinaryInfixKind::WeakUntil
+                | LTLBinaryInfixKind::Release
+                | LTLBinaryInfixKind::BoundedUntil(..),
+                Dialect::Native,
+            ) => 200,
+        }
+    }
+
+    pub fn is_right_associative(&self, dialect: Dialect) -> bool {
+        match dialect {
+            Dialect::Native | Dialect::NuSMV => matches!(self, LTLBinaryInfixKind::Implies),
+            Dialect::Spot => !matches!(self, LTLBinaryInfixKind::And | LTLBinaryInfixKind::Or),
         }
     }
 }
@@ -32,6 +59,10 @@ enum LTLUnaryPrefixKind {
     Next,
     Future,
     Generally,
+    WeakNext,
+    BoundedNext(u32),
+    BoundedFuture(u32, u32),
+    BoundedGenerally(u32, u32),
     Identity,
 }
 
@@ -54,24 +85,25 @@ enum LTLFormulaBuilding {
         LTLBinaryInfixKind,
         Option<Box<LTLFormulaBuilding>>,
         Option<Box<LTLFormulaBuilding>>,
+        Span,
     ),
-    UnaryPrefix(LTLUnaryPrefixKind, Option<Box<LTLFormulaBuilding>>),
+    UnaryPrefix(LTLUnaryPrefixKind, Option<Box<LTLFormulaBuilding>>, Span),
     Atomics(LTLAtomicKind),
 }
 
 impl LTLFormulaBuilding {
     fn get_right_child_mut(&mut self) -> Option<&mut LTLFormulaBuilding> {
         match self {
-            LTLFormulaBuilding::BinaryInfix(_, _, Some(phi))
-            | LTLFormulaBuilding::UnaryPrefix(_, Some(phi)) => Some(phi),
+            LTLFormulaBuilding::BinaryInfix(_, _, Some(phi), _)
+            | LTLFormulaBuilding::UnaryPrefix(_, Some(phi), _) => Some(phi),
             _ => None,
         }
     }
 
     fn get_right_child(&self) -> Option<&LTLFormulaBuilding> {
         match self {
-            LTLFormulaBuilding::BinaryInfix(_, _, Some(phi))
-            | LTLFormulaBuilding::UnaryPrefix(_, Some(phi)) => Some(phi),
+            LTLFormulaBuilding::BinaryInfix(_, _, Some(phi), _)
+            | LTLFormulaBuilding::UnaryPrefix(_, Some(phi), _) => Some(phi),
             _ => None,
         }
     }
@@ -88,18 +120,68 @@ impl LTLFormulaBuilding {
         }
     }
 
+    fn is_complete(&self) -> bool {
+        matches!(
+            self,
+            LTLFormulaBuilding::Atomics(_)
+                | LTLFormulaBuilding::BinaryInfix(_, _, Some(_), _)
+                | LTLFormulaBuilding::UnaryPrefix(_, Some(_), _)
+        )
+    }
+
+    /// Removes the rightmost leaf, which must not be `self`.
+    fn remove_rightmost_leaf(&mut self) {
+        let mut current = self;
+        while current
+            .get_right_child()
+            .unwrap()
+            .get_right_child()
+            .is_some()
+        {
+            current = current.get_right_child_mut().unwrap();
+        }
+        current.get_right_content_mut().unwrap().take();
+    }
+
+    /// Removes operators that are missing their right operand from the end of the formula and
+    /// returns the span of the first one. A binary operator is replaced by its left side.
+    fn remove_incomplete_operators(&mut self) -> Option<Span> {
+        let mut first = None;
+        loop {
+            let leaf = self.get_rightmost_leaf_mut();
+            let span = match leaf {
+                LTLFormulaBuilding::BinaryInfix(_, left, None, span) => {
+                    let span = *span;
+                    let left = left.take().unwrap();
+                    *leaf = *left;
+                    span
+                }
+                LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, None, _) => {
+                    return first
+                }
+                LTLFormulaBuilding::UnaryPrefix(_, None, span) => {
+                    let span = *span;
+                    self.remove_rightmost_leaf();
+                    span
+                }
+                _ => return first,
+            };
+            first.get_or_insert(span);
+        }
+    }
+
     fn get_right_content_mut(&mut self) -> Result<&mut Option<Box<LTLFormulaBuilding>>, ()> {
         match self {
-            LTLFormulaBuilding::BinaryInfix(_, _, ref mut phi)
-            | LTLFormulaBuilding::UnaryPrefix(_, ref mut phi) => Ok(phi),
+            LTLFormulaBuilding::BinaryInfix(_, _, ref mut phi, _)
+            | LTLFormulaBuilding::UnaryPrefix(_, ref mut phi, _) => Ok(phi),
             LTLFormulaBuilding::Atomics(_) => Err(()),
         }
     }
 
     fn add(&mut self, other: LTLFormulaBuilding) -> Result<(), ()> {
         match self {
-            LTLFormulaBuilding::BinaryInfix(_, _, ref mut phi)
-            | LTLFormulaBuilding::UnaryPrefix(_, ref mut phi) => {
+            LTLFormulaBuilding::BinaryInfix(_, _, ref mut phi, _)
+            | LTLFormulaBuilding::UnaryPrefix(_, ref mut phi, _) => {
                 if phi.is_some() {
                     return Err(());
                 }
@@ -110,7 +192,11 @@ impl LTLFormulaBuilding {
         }
     }
 
-    fn get_highest_node_with_prio_greater_than(&mut self, prio: u32) -> &mut LTLFormulaBuilding {
+    fn get_highest_node_with_prio_greater_than(
+        &mut self,
+        prio: u32,
+        dialect: Dialect,
+    ) -> &mut LTLFormulaBuilding {
         let mut current = self;
         loop {
             let next = current.get_right_child();
@@ -119,119 +205,256 @@ impl LTLFormulaBuilding {
                 return current;
             }
             let next_content = next.unwrap();
-            if next_content.operator_precedence() >= prio {
+            if next_content.operator_precedence(dialect) >= prio {
                 return current;
             }
             current = current.get_right_child_mut().unwrap();
         }
     }
 
-    fn operator_precedence(&self) -> u32 {
+    /// Deepest nesting of `X` once bounded operators are unrolled.
+    fn next_depth(&self) -> u32 {
+        let depth =
+            |phi: &Option<Box<LTLFormulaBuilding>>| phi.as_ref().map_or(0, |phi| phi.next_depth());
+        match self {
+            LTLFormulaBuilding::BinaryInfix(kind, phi1, phi2, _) => {
+                let own = match kind {
+                    LTLBinaryInfixKind::BoundedUntil(_, b) => *b,
+                    _ => 0,
+                };
+                own.saturating_add(depth(phi1).max(depth(phi2)))
+            }
+            LTLFormulaBuilding::UnaryPrefix(kind, phi, _) => {
+                let own = match kind {
+                    LTLUnaryPrefixKind::Next | LTLUnaryPrefixKind::WeakNext => 1,
+                    LTLUnaryPrefixKind::BoundedNext(b)
+                    | LTLUnaryPrefixKind::BoundedFuture(_, b)
+                    | LTLUnaryPrefixKind::BoundedGenerally(_, b) => *b,
+                    _ => 0,
+                };
+                own.saturating_add(depth(phi))
+            }
+            LTLFormulaBuilding::Atomics(_) => 0,
+        }
+    }
+
+    /// Span of the first bounded operator, if there is one.
+    fn first_bounded(&self) -> Option<Span> {
+        match self {
+            LTLFormulaBuilding::BinaryInfix(kind, phi1, phi2, span) => phi1
+                .as_ref()
+                .and_then(|phi| phi.first_bounded())
+                .or_else(|| matches!(kind, LTLBinaryInfixKind::BoundedUntil(..)).then_some(*span))
+                .or_else(|| phi2.as_ref().and_then(|phi| phi.first_bounded())),
+            LTLFormulaBuilding::UnaryPrefix(kind, phi, span) => match kind {
+                LTLUnaryPrefixKind::BoundedNext(_)
+                | LTLUnaryPrefixKind::BoundedFuture(..)
+                | LTLUnaryPrefixKind::BoundedGenerally(..) => Some(*span),
+                _ => phi.as_ref().and_then(|phi| phi.first_bounded()),
+            },
+            LTLFormulaBuilding::Atomics(_) => None,
+        }
+    }
+
+    fn operator_precedence(&self, dialect: Dialect) -> u32 {
         match self {
-            LTLFormulaBuilding::BinaryInfix(kind, _, _) => kind.operator_precedence(),
-            LTLFormulaBuilding::UnaryPrefix(kind, _) => kind.operator_precedence(),
+            LTLFormulaBuilding::BinaryInfix(kind, _, _, _) => kind.operator_precedence(dialect),
+            LTLFormulaBuilding::UnaryPrefix(kind, _, _) => kind.operator_precedence(),
             LTLFormulaBuilding::Atomics(_) => 1200,
         }
     }
 
-    fn to_formula(&self) -> Result<LTLFormula, ()> {
+    fn to_formula(&self) -> Result<LTLFormula, Span> {
         Ok(match self {
-            LTLFormulaBuilding::BinaryInfix(LTLBinaryInfixKind::And, Some(phi1), Some(phi2)) => {
+            LTLFormulaBuilding::BinaryInfix(LTLBinaryInfixKind::And, Some(phi1), Some(phi2), _) => {
                 LTLFormula::and(phi1.to_formula()?, phi2.to_formula()?)
             }
-            LTLFormulaBuilding::BinaryInfix(LTLBinaryInfixKind::Or, Some(phi1), Some(phi2)) => {
-                LTLFormula::not(LTLFormula::and(
-                    LTLFormula::not(phi1.to_formula()?),
-                    LTLFormula::not(phi2.to_formula()?),
-                ))
+            LTLFormulaBuilding::BinaryInfix(LTLBinaryInfixKind::Or, Some(phi1), Some(phi2), _) => {
+                LTLFormula::or(phi1.to_formula()?, phi2.to_formula()?)
             }
             LTLFormulaBuilding::BinaryInfix(
                 LTLBinaryInfixKind::Implies,
                 Some(phi1),
                 Some(phi2),
-            ) => LTLFormula::not(LTLFormula::and(
-                phi1.to_formula()?,
-                LTLFormula::not(phi2.to_formula()?),
-            )),
-            LTLFormulaBuilding::BinaryInfix(LTLBinaryInfixKind::Until, Some(phi1), Some(phi2)) => {
-                LTLFormula::until(phi1.to_formula()?, phi2.to_formula()?, false)
-            }
+                _,
+            ) => LTLFormula::implies(phi1.to_formula()?, phi2.to_formula()?),
+            LTLFormulaBuilding::BinaryInfix(
+                LTLBinaryInfixKind::Equivalence,
+                Some(phi1),
+                Some(phi2),
+                _,
+            ) => LTLFormula::equivalent(phi1.to_formula()?, phi2.to_formula()?),
+            LTLFormulaBuilding::BinaryInfix(
+                LTLBinaryInfixKind::Until,
+                Some(phi1),
+                Some(phi2),
+                _,
+            ) => LTLFormula::until(phi1.to_formula()?, phi2.to_formula()?, false),
             LTLFormulaBuilding::BinaryInfix(
                 LTLBinaryInfixKind::WeakUntil,
                 Some(phi1),
                 Some(phi2),
+                _,
             ) => LTLFormula::until(phi1.to_formula()?, phi2.to_formula()?, true),
             LTLFormulaBuilding::BinaryInfix(
                 LTLBinaryInfixKind::Release,
                 Some(phi1),
                 Some(phi2),
-            ) => LTLFormula::not(LTLFormula::until(
-                LTLFormula::not(phi1.to_formula()?),
-                LTLFormula::not(phi2.to_formula()?),
-                false,
-            )),
-            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Not, Some(phi)) => {
+                _,
+            ) => LTLFormula::release(phi1.to_formula()?, phi2.to_formula()?),
+            LTLFormulaBuilding::BinaryInfix(
+                LTLBinaryInfixKind::BoundedUntil(a, b),
+                Some(phi1),
+                Some(phi2),
+                _,
+            ) => LTLFormula::until_within(phi1.to_formula()?, phi2.to_formula()?, *a, *b),
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Not, Some(phi), _) => {
                 LTLFormula::not(phi.to_formula()?)
             }
-            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Next, Some(phi)) => {
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Next, Some(phi), _) => {
                 LTLFormula::next(phi.to_formula()?)
             }
-            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Future, Some(phi)) => {
-                LTLFormula::until(
-                    LTLFormula::not(LTLFormula::and(
-                        LTLFormula::ap(0),
-                        LTLFormula::not(LTLFormula::ap(0)),
-                    )),
-                    phi.to_formula()?,
-                    false,
-                )
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Future, Some(phi), _) => {
+                LTLFormula::eventually(phi.to_formula()?)
+            }
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Generally, Some(phi), _) => {
+                LTLFormula::always(phi.to_formula()?)
             }
-            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Generally, Some(phi)) => {
-                LTLFormula::not(LTLFormula::until(
-                    LTLFormula::not(LTLFormula::and(
-                        LTLFormula::ap(0),
-                        LTLFormula::not(LTLFormula::ap(0)),
-                    )),
-                    LTLFormula::not(phi.to_formula()?),
-                    false,
-                ))
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::WeakNext, Some(phi), _) => {
+                LTLFormula::weak_next(phi.to_formula()?)
             }
-            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, Some(phi)) => {
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::BoundedNext(n), Some(phi), _) => {
+                LTLFormula::next_n(phi.to_formula()?, *n)
+            }
+            LTLFormulaBuilding::UnaryPrefix(
+                LTLUnaryPrefixKind::BoundedFuture(a, b),
+                Some(phi),
+                _,
+            ) => LTLFormula::eventually_within(phi.to_formula()?, *a, *b),
+            LTLFormulaBuilding::UnaryPrefix(
+                LTLUnaryPrefixKind::BoundedGenerally(a, b),
+                Some(phi),
+                _,
+            ) => LTLFormula::always_within(phi.to_formula()?, *a, *b),
+            LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, Some(phi), _) => {
                 phi.to_formula()?
             }
             LTLFormulaBuilding::Atomics(LTLAtomicKind::AP(ap)) => LTLFormula::ap(*ap),
-            LTLFormulaBuilding::Atomics(LTLAtomicKind::False) => {
-                LTLFormula::and(LTLFormula::ap(0), LTLFormula::not(LTLFormula::ap(0)))
-            }
-            LTLFormulaBuilding::Atomics(LTLAtomicKind::True) => LTLFormula::not(LTLFormula::and(
-                LTLFormula::ap(0),
-                LTLFormula::not(LTLFormula::ap(0)),
-            )),
-            _ => return Err(()),
+            LTLFormulaBuilding::Atomics(LTLAtomicKind::False) => LTLFormula::falsity(),
+            LTLFormulaBuilding::Atomics(LTLAtomicKind::True) => LTLFormula::truth(),
+            LTLFormulaBuilding::BinaryInfix(_, _, _, span)
+            | LTLFormulaBuilding::UnaryPrefix(_, _, span) => return Err(*span),
         })
     }
 }
 
-pub fn parser(tokens: Vec<LTLToken>) -> Result<LTLFormula, ParsingError> {
+pub fn parser(
+    formula: &str,
+    tokens: Vec<SpannedToken>,
+    dialect: Dialect,
+) -> Result<LTLFormula, ParsingError> {
+    let (ast, mut errors) = recovering_parser(formula, tokens, dialect);
+    if errors.is_empty() {
+        Ok(ast.unwrap())
+    } else {
+        Err(errors.remove(0))
+    }
+}
+
+/// Closes the innermost parenthesis group and inserts it into the enclosing one.
+fn close_group(
+    formula: &str,
+    current: &mut Vec<LTLFormulaBuilding>,
+    errors: &mut Vec<ParsingError>,
+    close: Option<Span>,
+) {
+    let mut last = current.pop().unwrap();
+    if let Some(operator) = last.remove_incomplete_operators() {
+        errors.push(ParsingError::new(
+            ErrorKind::MissingOperand,
+            formula,
+            Some(operator),
+        ));
+    } else if let (
+        LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, None, open),
+        Some(close),
+    ) = (&last, close)
+    {
+        errors.push(ParsingError::new(
+            ErrorKind::EmptyParenthesis,
+            formula,
+            Some(open.to(&close)),
+        ));
+        // The operators waiting for the group lose their operand as well.
+        current.last_mut().unwrap().remove_incomplete_operators();
+    }
+    if last.is_complete() {
+        current
+            .last_mut()
+            .unwrap()
+            .get_rightmost_leaf_mut()
+            .add(last)
+            .unwrap();
+    }
+}
+
+/// Parses as much of the formula as possible and reports every syntax error, sorted by position.
+///
+/// After an error, the parser resynchronises: operands that follow another operand are skipped
+/// up to the next binary operator or closing parenthesis, binary operators without a left side
+/// and unmatched closing parentheses are dropped, operators without an operand are removed and
+/// unclosed parentheses are closed at the end. The returned formula is what remains, which is
+/// `None` if nothing does.
+pub fn recovering_parser(
+    formula: &str,
+    tokens: Vec<SpannedToken>,
+    dialect: Dialect,
+) -> (Option<LTLFormula>, Vec<ParsingError>) {
+    let error = |kind, span| ParsingError::new(kind, formula, Some(span));
+    let mut errors = Vec::<ParsingError>::new();
     let mut current = vec![LTLFormulaBuilding::UnaryPrefix(
         LTLUnaryPrefixKind::Identity,
         None,
+        Span::new(0, 0),
     )];
+    // Parenthesis depth while skipping an unexpected operand.
+    let mut skipping: Option<usize> = None;
+
+    for (token, span) in tokens {
+        if let Some(depth) = skipping {
+            match token {
+                LTLToken::OpenParenthesis => {
+                    skipping = Some(depth + 1);
+                    continue;
+                }
+                LTLToken::CloseParenthesis if depth > 0 => {
+                    skipping = Some(depth - 1);
+                    continue;
+                }
+                LTLToken::CloseParenthesis | LTLToken::BinaryInfix(_) if depth == 0 => {
+                    skipping = None;
+                }
+                _ => continue,
+            }
+        }
 
-    for token in &tokens {
         match token {
             LTLToken::Atomic(atomic) => {
                 let to_insert = match atomic {
-                    LTLTokenAtomic::AP(ap) => LTLAtomicKind::AP(*ap),
+                    LTLTokenAtomic::AP(ap) => LTLAtomicKind::AP(ap),
                     LTLTokenAtomic::True => LTLAtomicKind::True,
                     LTLTokenAtomic::False => LTLAtomicKind::False,
                 };
-                current
+                if current
                     .last_mut()
                     .unwrap()
                     .get_rightmost_leaf_mut()
                     .add(LTLFormulaBuilding::Atomics(to_insert))
-                    .map_err(|_| ParsingError::new(ShittySyntax, "", None))?;
+                    .is_err()
+                {
+                    errors.push(error(ErrorKind::AdjacentOperands, span));
+                    skipping = Some(0);
+                }
             }
             LTLToken::UnaryPrefix(unary_prefix) => {
                 let to_insert = match unary_prefix {
@@ -239,83 +462,126 @@ pub fn parser(tokens: Vec<LTLToken>) -> Result<LTLFormula, ParsingError> {
                     LTLTokenUnaryPrefix::Not => LTLUnaryPrefixKind::Not,
                     LTLTokenUnaryPrefix::Future => LTLUnaryPrefixKind::Future,
                     LTLTokenUnaryPrefix::Generally => LTLUnaryPrefixKind::Generally,
+                    LTLTokenUnaryPrefix::WeakNext => LTLUnaryPrefixKind::WeakNext,
+                    LTLTokenUnaryPrefix::BoundedNext(n) => LTLUnaryPrefixKind::BoundedNext(n),
+                    LTLTokenUnaryPrefix::BoundedFuture(a, b) => {
+                        LTLUnaryPrefixKind::BoundedFuture(a, b)
+                    }
+                    LTLTokenUnaryPrefix::BoundedGenerally(a, b) => {
+                        LTLUnaryPrefixKind::BoundedGenerally(a, b)
+                    }
                 };
-                current
+                if current
                     .last_mut()
                     .unwrap()
                     .get_rightmost_leaf_mut()
-                    .add(LTLFormulaBuilding::UnaryPrefix(to_insert, None))
-                    .map_err(|_| ParsingError::new(ShittySyntax, "", None))?;
+                    .add(LTLFormulaBuilding::UnaryPrefix(to_insert, None, span))
+                    .is_err()
+                {
+                    errors.push(error(ErrorKind::AdjacentOperands, span));
+                    skipping = Some(0);
+                }
             }
             LTLToken::BinaryInfix(binary_infix) => {
                 let to_insert = match binary_infix {
                     LTLTokenBinaryInfix::And => LTLBinaryInfixKind::And,
                     LTLTokenBinaryInfix::Or => LTLBinaryInfixKind::Or,
                     LTLTokenBinaryInfix::Implies => LTLBinaryInfixKind::Implies,
+                    LTLTokenBinaryInfix::Equivalence => LTLBinaryInfixKind::Equivalence,
                     LTLTokenBinaryInfix::Until => LTLBinaryInfixKind::Until,
                     LTLTokenBinaryInfix::WeakUntil => LTLBinaryInfixKind::WeakUntil,
                     LTLTokenBinaryInfix::Release => LTLBinaryInfixKind::Release,
+                    LTLTokenBinaryInfix::BoundedUntil(a, b) => {
+                        LTLBinaryInfixKind::BoundedUntil(a, b)
+                    }
+                };
+                if !current
+                    .last_mut()
+                    .unwrap()
+                    .get_rightmost_leaf_mut()
+                    .is_complete()
+                {
+                    errors.push(error(ErrorKind::MissingLeftOperand, span));
+                    continue;
+                }
+                // A right associative operator becomes the right side of an equal operator
+                // before it.
+                let precedence = if to_insert.is_right_associative(dialect) {
+                    to_insert.operator_precedence(dialect) + 1
+                } else {
+                    to_insert.operator_precedence(dialect)
                 };
                 let parent = current
                     .last_mut()
                     .unwrap()
-                    .get_highest_node_with_prio_greater_than(to_insert.operator_precedence());
-                let right_side = parent
-                    .get_right_content_mut()
-                    .map_err(|_| ParsingError::new(ShittySyntax, "", None))?;
-                let right_side_value = right_side.take();
+                    .get_highest_node_with_prio_greater_than(precedence, dialect);
+                let right_side_value = parent.get_right_content_mut().unwrap().take();
                 parent
                     .add(LTLFormulaBuilding::BinaryInfix(
                         to_insert,
                         right_side_value,
                         None,
+                        span,
                     ))
-                    .map_err(|_| ParsingError::new(ShittySyntax, "", None))?;
+                    .unwrap();
             }
             LTLToken::OpenParenthesis => {
+                if current
+                    .last_mut()
+                    .unwrap()
+                    .get_rightmost_leaf_mut()
+                    .is_complete()
+                {
+                    errors.push(error(ErrorKind::AdjacentOperands, span));
+                    skipping = Some(1);
+                    continue;
+                }
                 current.push(LTLFormulaBuilding::UnaryPrefix(
                     LTLUnaryPrefixKind::Identity,
                     None,
+                    span,
                 ));
             }
             LTLToken::CloseParenthesis => {
                 if current.len() <= 1 {
-                    return Err(ParsingError::new(
-                        ErrorKind::UnmatchedCloseParenthesis,
-                        "",
-                        None,
-                    ));
-                }
-                let mut last = current.pop().unwrap();
-                if let LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, None) =
-                    last.get_rightmost_leaf_mut()
-                {
-                    return Err(ParsingError::new(ErrorKind::EmptyParenthesis, "", None));
+                    errors.push(error(ErrorKind::UnmatchedCloseParenthesis, span));
+                    continue;
                 }
-                let _ = current
-                    .last_mut()
-                    .unwrap()
-                    .get_rightmost_leaf_mut()
-                    .add(last);
+                close_group(formula, &mut current, &mut errors, Some(span));
             }
         }
     }
 
-    if current.len() > 1 {
-        return Err(ParsingError::new(
-            ErrorKind::UnmatchedOpenParenthesis,
-            "",
-            None,
-        ));
+    while current.len() > 1 {
+        let LTLFormulaBuilding::UnaryPrefix(_, _, open) = current.last().unwrap() else {
+            unreachable!()
+        };
+        errors.push(error(ErrorKind::UnmatchedOpenParenthesis, *open));
+        close_group(formula, &mut current, &mut errors, None);
     }
 
-    return if let Some(first) = current.first() {
-        first
-            .to_formula()
-            .map_err(|_| ParsingError::new(ShittySyntax, "", None))
-    } else {
-        Err(ParsingError::new(ErrorKind::ShittySyntax, "", None))
+    let mut root = current.pop().unwrap();
+    if let Some(operator) = root.remove_incomplete_operators() {
+        errors.push(error(ErrorKind::MissingOperand, operator));
+    }
+    let ast = match root {
+        LTLFormulaBuilding::UnaryPrefix(LTLUnaryPrefixKind::Identity, None, _) => {
+            if errors.is_empty() {
+                errors.push(ParsingError::new(ErrorKind::EmptyFormula, formula, None));
+            }
+            None
+        }
+        // Unrolling is checked before it happens, since deep formulas take too long to check.
+        root => match root.first_bounded() {
+            Some(span) if root.next_depth() > MAX_BOUND => {
+                errors.push(error(ErrorKind::BoundTooLarge, span));
+                None
+            }
+            _ => Some(root.to_formula().unwrap()),
+        },
     };
+    errors.sort_by_key(|error| error.at());
+    (ast, errors)
 }
 
 #[cfg(test)]
@@ -327,18 +593,45 @@ mod tests {
     use LTLTokenBinaryInfix as B;
     use LTLTokenUnaryPrefix as U;
 
+    fn parser(formula: &str, tokens: Vec<SpannedToken>) -> Result<LTLFormula, ParsingError> {
+        super::parser(formula, tokens, Dialect::Native)
+    }
+
+    fn recovering_parser(
+        formula: &str,
+        tokens: Vec<SpannedToken>,
+    ) -> (Option<LTLFormula>, Vec<ParsingError>) {
+        super::recovering_parser(formula, tokens, Dialect::Native)
+    }
+
+    fn spanned(tokens: Vec<LTLToken>) -> Vec<SpannedToken> {
+        tokens
+            .into_iter()
+            .enumerate()
+            .map(|(i, token)| (token, Span::new(i, i + 1)))
+            .collect()
+    }
+
+    fn parse_error(formula: &str) -> ParsingError {
+        let (tokens, _) = crate::parsing::lexer::lexer(formula, Dialect::Native).unwrap();
+        parser(formula, tokens).unwrap_err()
+    }
+
     #[test]
     fn test_basic_parsing() {
         assert_eq!(
-            parser(vec![
-                L::UnaryPrefix(U::Next),
-                L::Atomic(A::AP(0)),
-                L::BinaryInfix(B::And),
-                L::Atomic(A::AP(1)),
-                L::BinaryInfix(B::Until),
-                L::UnaryPrefix(U::Not),
-                L::Atomic(A::AP(0))
-            ]),
+            parser(
+                "",
+                spanned(vec![
+                    L::UnaryPrefix(U::Next),
+                    L::Atomic(A::AP(0)),
+                    L::BinaryInfix(B::And),
+                    L::Atomic(A::AP(1)),
+                    L::BinaryInfix(B::Until),
+                    L::UnaryPrefix(U::Not),
+                    L::Atomic(A::AP(0))
+                ])
+            ),
             Ok(F::until(
                 F::and(F::next(F::ap(0)), F::ap(1),),
                 F::not(F::ap(0)),
@@ -347,69 +640,208 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_implies_and_equivalence() {
+        let parse = |formula| {
+            let (tokens, _) = crate::parsing::lexer::lexer(formula, Dialect::Native).unwrap();
+            parser(formula, tokens).unwrap()
+        };
+        assert_eq!(
+            parse("a -> b -> c"),
+            F::implies(F::ap(0), F::implies(F::ap(1), F::ap(2)))
+        );
+        assert_eq!(
+            parse("a & b -> c | a"),
+            F::implies(
+                F::and(F::ap(0), F::ap(1)),
+                F::not(F::and(F::not(F::ap(2)), F::not(F::ap(0))))
+            )
+        );
+        assert_eq!(
+            parse("a <-> b -> c <-> a"),
+            F::equivalent(
+                F::equivalent(F::ap(0), F::implies(F::ap(1), F::ap(2))),
+                F::ap(0)
+            )
+        );
+        assert_eq!(parse("□(a → ◇b)"), parse("G(a -> F b)"));
+    }
+
     #[test]
     fn test_proper_errors() {
-        assert_eq!(parser(vec![]).unwrap_err().kind(), ShittySyntax);
         assert_eq!(
-            parser(vec![L::Atomic(A::AP(0)), L::Atomic(A::AP(1))])
-                .unwrap_err()
-                .kind(),
-            ShittySyntax
+            parser("", vec![]).unwrap_err().kind(),
+            &ErrorKind::EmptyFormula
         );
         assert_eq!(
-            parser(vec![L::BinaryInfix(B::Until), L::Atomic(A::AP(0))])
+            parser("", spanned(vec![L::Atomic(A::AP(0)), L::Atomic(A::AP(1))]))
                 .unwrap_err()
                 .kind(),
-            ShittySyntax
+            &ErrorKind::AdjacentOperands
         );
         assert_eq!(
-            parser(vec![L::Atomic(A::AP(0)), L::UnaryPrefix(U::Next)])
-                .unwrap_err()
-                .kind(),
-            ShittySyntax
+            parser(
+                "",
+                spanned(vec![L::BinaryInfix(B::Until), L::Atomic(A::AP(0))])
+            )
+            .unwrap_err()
+            .kind(),
+            &ErrorKind::MissingLeftOperand
+        );
+        assert_eq!(
+            parser(
+                "",
+                spanned(vec![L::Atomic(A::AP(0)), L::UnaryPrefix(U::Next)])
+            )
+            .unwrap_err()
+            .kind(),
+            &ErrorKind::AdjacentOperands
         );
         assert_eq!(
-            parser(vec![
-                L::OpenParenthesis,
-                L::OpenParenthesis,
-                L::Atomic(A::AP(0)),
-                L::CloseParenthesis
-            ])
+            parser(
+                "",
+                spanned(vec![
+                    L::OpenParenthesis,
+                    L::OpenParenthesis,
+                    L::Atomic(A::AP(0)),
+                    L::CloseParenthesis
+                ])
+            )
             .unwrap_err()
             .kind(),
-            ErrorKind::UnmatchedOpenParenthesis
+            &ErrorKind::UnmatchedOpenParenthesis
         );
         assert_eq!(
-            parser(vec![
-                L::OpenParenthesis,
-                L::Atomic(A::AP(0)),
-                L::CloseParenthesis,
-                L::CloseParenthesis
-            ])
+            parser(
+                "",
+                spanned(vec![
+                    L::OpenParenthesis,
+                    L::Atomic(A::AP(0)),
+                    L::CloseParenthesis,
+                    L::CloseParenthesis
+                ])
+            )
             .unwrap_err()
             .kind(),
-            ErrorKind::UnmatchedCloseParenthesis
+            &ErrorKind::UnmatchedCloseParenthesis
         );
         assert_eq!(
-            parser(vec![
-                L::CloseParenthesis,
-                L::Atomic(A::AP(0)),
-                L::OpenParenthesis
-            ])
+            parser(
+                "",
+                spanned(vec![
+                    L::CloseParenthesis,
+                    L::Atomic(A::AP(0)),
+                    L::OpenParenthesis
+                ])
+            )
             .unwrap_err()
             .kind(),
-            ErrorKind::UnmatchedCloseParenthesis
+            &ErrorKind::UnmatchedCloseParenthesis
         );
         assert_eq!(
-            parser(vec![
-                L::Atomic(A::AP(0)),
-                L::BinaryInfix(B::And),
-                L::OpenParenthesis,
-                L::CloseParenthesis
-            ])
+            parser(
+                "",
+                spanned(vec![
+                    L::Atomic(A::AP(0)),
+                    L::BinaryInfix(B::And),
+                    L::OpenParenthesis,
+                    L::CloseParenthesis
+                ])
+            )
             .unwrap_err()
             .kind(),
-            ErrorKind::EmptyParenthesis
+            &ErrorKind::EmptyParenthesis
         )
     }
+
+    #[test]
+    fn test_error_spans() {
+        let error = parse_error("G(a & )");
+        assert_eq!(error.kind(), &ErrorKind::MissingOperand);
+        assert_eq!(error.token(), Some("&".to_string()));
+        let error = parse_error("a & X");
+        assert_eq!(error.kind(), &ErrorKind::MissingOperand);
+        assert_eq!(error.span(), Some(&Span::new(4, 5)));
+        let error = parse_error("a & | b");
+        assert_eq!(error.kind(), &ErrorKind::MissingLeftOperand);
+        assert_eq!(error.token(), Some("|".to_string()));
+        let error = parse_error("foo bar");
+        assert_eq!(error.kind(), &ErrorKind::AdjacentOperands);
+        assert_eq!(error.token(), Some("bar".to_string()));
+        let error = parse_error("a (b)");
+        assert_eq!(error.kind(), &ErrorKind::AdjacentOperands);
+        assert_eq!(error.at(), 2);
+        let error = parse_error("(a & (b)");
+        assert_eq!(error.kind(), &ErrorKind::UnmatchedOpenParenthesis);
+        assert_eq!(error.at(), 0);
+        let error = parse_error("a & ( )");
+        assert_eq!(error.kind(), &ErrorKind::EmptyParenthesis);
+        assert_eq!(error.span(), Some(&Span::new(4, 7)));
+    }
+
+    fn recover(formula: &str) -> (Option<LTLFormula>, Vec<(ErrorKind, usize)>) {
+        let (tokens, _) = crate::parsing::lexer::lexer(formula, Dialect::Native).unwrap();
+        let (ast, errors) = recovering_parser(formula, tokens);
+        (
+            ast,
+            errors
+                .into_iter()
+                .map(|error| (error.kind().clone(), error.at()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_recovery() {
+        assert_eq!(
+            recover("a b & (c &) | & d"),
+            (
+                Some(F::not(F::and(
+                    F::not(F::and(F::ap(0), F::ap(2))),
+                    F::not(F::ap(3))
+                ))),
+                vec![
+                    (ErrorKind::AdjacentOperands, 2),
+                    (ErrorKind::MissingOperand, 9),
+                    (ErrorKind::MissingLeftOperand, 14)
+                ]
+            )
+        );
+        assert_eq!(
+            recover("(a (b c)) ) & X"),
+            (
+                Some(F::ap(0)),
+                vec![
+                    (ErrorKind::AdjacentOperands, 3),
+                    (ErrorKind::UnmatchedCloseParenthesis, 10),
+                    (ErrorKind::MissingOperand, 14)
+                ]
+            )
+        );
+        assert_eq!(
+            recover("G (a U () & (b"),
+            (
+                Some(F::not(F::until(
+                    F::not(F::and(F::ap(0), F::not(F::ap(0)))),
+                    F::not(F::and(F::ap(0), F::ap(1))),
+                    false
+                ))),
+                vec![
+                    (ErrorKind::UnmatchedOpenParenthesis, 2),
+                    (ErrorKind::EmptyParenthesis, 7),
+                    (ErrorKind::UnmatchedOpenParenthesis, 12)
+                ]
+            )
+        );
+        assert_eq!(
+            recover("& |"),
+            (
+                None,
+                vec![
+                    (ErrorKind::MissingLeftOperand, 0),
+                    (ErrorKind::MissingLeftOperand, 2)
+                ]
+            )
+        );
+    }
 }
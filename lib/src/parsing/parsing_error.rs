@@ -1,50 +1,153 @@
+use crate::parsing::lexer::MAX_BOUND;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
+/// Range of characters `start..end` in a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// Smallest span covering both `self` and `other`.
+    pub fn to(&self, other: &Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ErrorKind {
     UnexpectedToken,
-    ShittySyntax,
+    /// A quoted proposition is missing its closing quote.
+    UnterminatedQuote,
+    EmptyFormula,
+    /// An operator is not followed by its (right) operand.
+    MissingOperand,
+    /// A binary operator has nothing on its left side.
+    MissingLeftOperand,
+    /// Two operands follow each other without a binary operator between them.
+    AdjacentOperands,
     UnmatchedOpenParenthesis,
     UnmatchedCloseParenthesis,
     EmptyParenthesis,
     NoAPs,
+    /// The bounds of a bounded operator are malformed or empty, as in `F[3,1]`.
+    InvalidBound,
+    /// Bounded operators unroll to more than [`MAX_BOUND`] nested `X`.
+    BoundTooLarge,
+    /// A word such as `Grant` begins with an operator letter, so it would be read as `G rant`.
+    /// Propositions like it have to be quoted.
+    OperatorInName,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsingError {
     kind: ErrorKind,
     formula: String,
-    at: usize,
+    span: Option<Span>,
 }
 
 impl ParsingError {
-    pub fn new(kind: ErrorKind, str: &str, at: Option<usize>) -> ParsingError {
+    pub fn new(kind: ErrorKind, str: &str, span: Option<Span>) -> ParsingError {
         ParsingError {
             kind,
             formula: str.to_string(),
-            at: at.unwrap_or(0),
+            span,
         }
     }
 
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    pub fn formula(&self) -> &str {
+        &self.formula
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Character offset the error starts at.
+    pub fn at(&self) -> usize {
+        self.span.map(|span| span.start).unwrap_or(0)
+    }
+
+    /// The part of the formula the error refers to.
+    pub fn token(&self) -> Option<String> {
+        self.span.map(|span| {
+            self.formula
+                .chars()
+                .skip(span.start)
+                .take(span.end - span.start)
+                .collect()
+        })
+    }
 }
 
 impl Error for ParsingError {}
 
 impl Display for ParsingError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let error_msg = match self.kind {
-            ErrorKind::UnexpectedToken => "Unexpected token",
-            ErrorKind::ShittySyntax => "Syntax error",
-            ErrorKind::UnmatchedOpenParenthesis => "Unmatched open parenthesis",
-            ErrorKind::UnmatchedCloseParenthesis => "Unmatched close parenthesis",
-            ErrorKind::EmptyParenthesis => "Empty parenthesis",
-            ErrorKind::NoAPs => "No atomic propositions",
-        };
-        write!(f, "{}", error_msg)
+        let token = self.token().unwrap_or_default();
+        match self.kind {
+            ErrorKind::UnexpectedToken => write!(f, "Unexpected token '{}'", token)?,
+            ErrorKind::UnterminatedQuote => write!(f, "Unterminated quote")?,
+            ErrorKind::EmptyFormula => write!(f, "Formula is empty")?,
+            ErrorKind::MissingOperand => write!(f, "Operator '{}' is missing its operand", token)?,
+            ErrorKind::MissingLeftOperand => write!(f, "Operator '{}' has no left operand", token)?,
+            ErrorKind::AdjacentOperands => write!(f, "Missing operator before '{}'", token)?,
+            ErrorKind::UnmatchedOpenParenthesis => write!(f, "Unmatched open parenthesis")?,
+            ErrorKind::UnmatchedCloseParenthesis => write!(f, "Unmatched close parenthesis")?,
+            ErrorKind::EmptyParenthesis => write!(f, "Empty parenthesis")?,
+            ErrorKind::NoAPs => write!(f, "No atomic propositions")?,
+            ErrorKind::InvalidBound => write!(f, "Invalid bounds '{}'", token)?,
+            ErrorKind::BoundTooLarge => write!(
+                f,
+                "Bounds '{}' unroll to more than {} nested X",
+                token, MAX_BOUND
+            )?,
+            ErrorKind::OperatorInName => write!(
+                f,
+                "'{}' begins with an operator, write \"{}\" for the proposition or separate the operator",
+                token, token
+            )?,
+        }
+        if let Some(span) = self.span {
+            write!(
+                f,
+                " at position {}\n{}\n{}{}",
+                span.start,
+                self.formula,
+                " ".repeat(span.start),
+                "^".repeat((span.end - span.start).max(1))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let error = ParsingError::new(ErrorKind::MissingOperand, "G(a & )", Some(Span::new(4, 5)));
+        assert_eq!(
+            error.to_string(),
+            "Operator '&' is missing its operand at position 4\nG(a & )\n    ^"
+        );
+        let error = ParsingError::new(ErrorKind::EmptyFormula, "", None);
+        assert_eq!(error.to_string(), "Formula is empty");
     }
 }
@@ -0,0 +1,417 @@
+use crate::parsing::lexer::{identifier_end, keywords, starts_name};
+use crate::parsing::parser::LTLBinaryInfixKind;
+use crate::parsing::{Dialect, LTLFormula};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+enum Unary {
+    Not,
+    Next,
+    Future,
+    Generally,
+}
+
+/// A formula with the operators the parser desugars restored.
+#[derive(Clone)]
+enum Term {
+    AP(u8),
+    True,
+    False,
+    Unary(Unary, Box<Term>),
+    Binary(LTLBinaryInfixKind, Box<Term>, Box<Term>),
+}
+
+impl Term {
+    fn unary(operator: Unary, phi: &LTLFormula, constants: bool) -> Term {
+        Term::Unary(operator, Box::new(Term::new(phi, constants)))
+    }
+
+    fn binary(
+        operator: LTLBinaryInfixKind,
+        phi1: &LTLFormula,
+        phi2: &LTLFormula,
+        constants: bool,
+    ) -> Term {
+        Term::Binary(
+            operator,
+            Box::new(Term::new(phi1, constants)),
+            Box::new(Term::new(phi2, constants)),
+        )
+    }
+
+    /// Whether proposition `ap` occurs in the term.
+    fn contains(&self, ap: u8) -> bool {
+        match self {
+            Term::AP(other) => *other == ap,
+            Term::True | Term::False => false,
+            Term::Unary(_, phi) => phi.contains(ap),
+            Term::Binary(_, phi1, phi2) => phi1.contains(ap) || phi2.contains(ap),
+        }
+    }
+
+    /// Replaces the weak until, which NuSMV does not have, by `(phi1 U phi2) | G phi1`.
+    fn without_weak_until(self) -> Term {
+        match self {
+            Term::Binary(LTLBinaryInfixKind::WeakUntil, phi1, phi2) => {
+                let phi1 = phi1.without_weak_until();
+                let phi2 = phi2.without_weak_until();
+                let generally = Term::Unary(Unary::Generally, Box::new(phi1.clone()));
+                Term::Binary(
+                    LTLBinaryInfixKind::Or,
+                    Box::new(Term::Binary(
+                        LTLBinaryInfixKind::Until,
+                        Box::new(phi1),
+                        Box::new(phi2),
+                    )),
+                    Box::new(generally),
+                )
+            }
+            Term::Binary(operator, phi1, phi2) => Term::Binary(
+                operator,
+                Box::new(phi1.without_weak_until()),
+                Box::new(phi2.without_weak_until()),
+            ),
+            Term::Unary(operator, phi) => Term::Unary(operator, Box::new(phi.without_weak_until())),
+            term => term,
+        }
+    }
+}
+
+/// Whether `phi` is `p0 & !p0`, as which the parser encodes `false`.
+fn is_false(phi: &LTLFormula) -> bool {
+    let p0 = LTLFormula::AP(0);
+    matches!(phi, LTLFormula::And(phi1, phi2)
+        if **phi1 == p0 && **phi2 == LTLFormula::Not(Box::new(p0.clone())))
+}
+
+fn is_true(phi: &LTLFormula) -> bool {
+    matches!(phi, LTLFormula::Not(phi) if is_false(phi))
+}
+
+/// Whether `phi` is printed as a negation.
+fn is_negation(phi: &LTLFormula) -> bool {
+    match phi {
+        LTLFormula::Not(_) if is_true(phi) => false,
+        LTLFormula::Not(inner) => match &**inner {
+            LTLFormula::Until(false, phi1, phi2) => {
+                !matches!(**phi2, LTLFormula::Not(_))
+                    || !(is_true(phi1) || matches!(**phi1, LTLFormula::Not(_)))
+            }
+            LTLFormula::And(_, phi2) => !matches!(**phi2, LTLFormula::Not(_)),
+            _ => true,
+        },
+        _ => false,
+    }
+}
+
+/// Operands of `phi1 -> phi2`, which is expressed as `!(phi1 & !phi2)`.
+fn as_implication(phi: &LTLFormula) -> Option<(&LTLFormula, &LTLFormula)> {
+    match phi {
+        LTLFormula::Not(phi) => match &**phi {
+            LTLFormula::And(phi1, phi2) => match &**phi2 {
+                LTLFormula::Not(phi2) => Some((phi1, phi2)),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl Term {
+    /// Restores the desugared operators of `phi`. The encodings of `true` and `false` are only
+    /// printed as constants if `constants`.
+    fn new(phi: &LTLFormula, constants: bool) -> Term {
+        if constants && is_true(phi) {
+            return Term::True;
+        }
+        if constants && is_false(phi) {
+            return Term::False;
+        }
+        match phi {
+            LTLFormula::AP(ap) => Term::AP(*ap),
+            LTLFormula::Not(inner) => match &**inner {
+                LTLFormula::Until(false, phi1, phi2) => match (&**phi1, &**phi2) {
+                    (phi1, LTLFormula::Not(phi2)) if is_true(phi1) => {
+                        Term::unary(Unary::Generally, phi2, constants)
+                    }
+                    (LTLFormula::Not(phi1), LTLFormula::Not(phi2)) => {
+                        Term::binary(LTLBinaryInfixKind::Release, phi1, phi2, constants)
+                    }
+                    _ => Term::unary(Unary::Not, inner, constants),
+                },
+                // `!(!phi1 & !phi2)` is printed as `phi1 | phi2` unless `!phi1` is itself
+                // sugar, `(a -> b) -> c` is not printed as `a & !b | c`.
+                LTLFormula::And(phi1, phi2) => match (&**phi1, &**phi2) {
+                    (LTLFormula::Not(left), LTLFormula::Not(phi2)) if is_negation(phi1) => {
+                        Term::binary(LTLBinaryInfixKind::Or, left, phi2, constants)
+                    }
+                    (phi1, LTLFormula::Not(phi2)) => {
+                        Term::binary(LTLBinaryInfixKind::Implies, phi1, phi2, constants)
+                    }
+                    _ => Term::unary(Unary::Not, inner, constants),
+                },
+                _ => Term::unary(Unary::Not, inner, constants),
+            },
+            LTLFormula::And(phi1, phi2) => match (as_implication(phi1), as_implication(phi2)) {
+                (Some((left1, right1)), Some((left2, right2)))
+                    if left1 == right2 && right1 == left2 =>
+                {
+                    Term::binary(LTLBinaryInfixKind::Equivalence, left1, right1, constants)
+                }
+                _ => Term::binary(LTLBinaryInfixKind::And, phi1, phi2, constants),
+            },
+            LTLFormula::Next(phi) => Term::unary(Unary::Next, phi, constants),
+            LTLFormula::Until(false, phi1, phi2) if is_true(phi1) => {
+                Term::unary(Unary::Future, phi2, constants)
+            }
+            LTLFormula::Until(false, phi1, phi2) => {
+                Term::binary(LTLBinaryInfixKind::Until, phi1, phi2, constants)
+            }
+            LTLFormula::Until(true, phi1, phi2) => {
+                Term::binary(LTLBinaryInfixKind::WeakUntil, phi1, phi2, constants)
+            }
+        }
+    }
+}
+
+struct Printer<'a> {
+    names: HashMap<u8, &'a str>,
+    dialect: Dialect,
+}
+
+impl Printer<'_> {
+    fn unary(operator: &Unary) -> &'static str {
+        match operator {
+            Unary::Not => "!",
+            Unary::Next => "X ",
+            Unary::Future => "F ",
+            Unary::Generally => "G ",
+        }
+    }
+
+    fn binary(&self, operator: &LTLBinaryInfixKind) -> &'static str {
+        match operator {
+            LTLBinaryInfixKind::And => " & ",
+            LTLBinaryInfixKind::Or => " | ",
+            LTLBinaryInfixKind::Implies => " -> ",
+            LTLBinaryInfixKind::Equivalence => " <-> ",
+            LTLBinaryInfixKind::Until => " U ",
+            LTLBinaryInfixKind::WeakUntil => " W ",
+            LTLBinaryInfixKind::Release if self.dialect == Dialect::NuSMV => " V ",
+            LTLBinaryInfixKind::Release => " R ",
+            LTLBinaryInfixKind::BoundedUntil(..) => {
+                unreachable!("bounded operators are unrolled when parsing")
+            }
+        }
+    }
+
+    /// Propositions without a name are called `p` followed by their number.
+    fn name(&self, ap: u8) -> Result<String, String> {
+        let Some(name) = self.names.get(&ap) else {
+            return Ok(format!("p{}", ap));
+        };
+        let chars: Vec<char> = name.chars().collect();
+        let is_identifier = chars.first().is_some_and(|c| starts_name(*c, self.dialect))
+            && identifier_end(&chars, 0, self.dialect) == chars.len()
+            && keywords(self.dialect)
+                .iter()
+                .all(|(keyword, _)| keyword != name);
+        if is_identifier {
+            Ok(name.to_string())
+        } else if self.dialect == Dialect::NuSMV {
+            Err(name.to_string())
+        } else {
+            Ok(format!(
+                "\"{}\"",
+                name.replace('\\', "\\\\").replace('"', "\\\"")
+            ))
+        }
+    }
+
+    /// Prints `term`, in parentheses if it would otherwise be parsed as a different operand.
+    fn operand(&self, term: &Term, parenthesise: bool) -> Result<String, String> {
+        if parenthesise {
+            Ok(format!("({})", self.print(term)?))
+        } else {
+            self.print(term)
+        }
+    }
+
+    fn print(&self, term: &Term) -> Result<String, String> {
+        Ok(match term {
+            Term::AP(ap) => self.name(*ap)?,
+            Term::True if self.dialect == Dialect::NuSMV => "TRUE".to_string(),
+            Term::False if self.dialect == Dialect::NuSMV => "FALSE".to_string(),
+            Term::True => "true".to_string(),
+            Term::False => "false".to_string(),
+            Term::Unary(operator, phi) => {
+                let parenthesise = matches!(**phi, Term::Binary(..));
+                format!(
+                    "{}{}",
+                    Self::unary(operator),
+                    self.operand(phi, parenthesise)?
+                )
+            }
+            Term::Binary(operator, phi1, phi2) => {
+                let precedence = operator.operator_precedence(self.dialect);
+                // Operators of the same precedence group to the left, unless the right one is
+                // right associative.
+                let parenthesise_left = match &**phi1 {
+                    Term::Binary(left, ..) => {
+                        let left_precedence = left.operator_precedence(self.dialect);
+                        left_precedence < precedence
+                            || left_precedence == precedence
+                                && operator.is_right_associative(self.dialect)
+                    }
+                    _ => false,
+                };
+                let parenthesise_right = match &**phi2 {
+                    Term::Binary(right, ..) => {
+                        let right_precedence = right.operator_precedence(self.dialect);
+                        right_precedence < precedence
+                            || right_precedence == precedence
+                                && !right.is_right_associative(self.dialect)
+                    }
+                    _ => false,
+                };
+                format!(
+                    "{}{}{}",
+                    self.operand(phi1, parenthesise_left)?,
+                    self.binary(operator),
+                    self.operand(phi2, parenthesise_right)?
+                )
+            }
+        })
+    }
+}
+
+/// Prints `formula` in the syntax of `dialect`, naming its propositions as in `ap_map`. The
+/// operators the parser desugars are restored where their pattern is recognised, and only the
+/// parentheses the precedence of the operators requires are printed.
+///
+/// Fails with the name of a proposition that cannot be written in `dialect`.
+pub fn print(
+    formula: &LTLFormula,
+    ap_map: &HashMap<String, u8>,
+    dialect: Dialect,
+) -> Result<String, String> {
+    let printer = Printer {
+        names: ap_map
+            .iter()
+            .map(|(name, ap)| (*ap, name.as_str()))
+            .collect(),
+        dialect,
+    };
+    // The parser encodes `true` and `false` with the first proposition, so they are only
+    // printed as constants if it still occurs in the output.
+    let mut term = Term::new(formula, true);
+    if !term.contains(0) {
+        term = Term::new(formula, false);
+    }
+    if dialect == Dialect::NuSMV {
+        term = term.without_weak_until();
+    }
+    printer.print(&term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse_in;
+
+    fn translate(formula: &str, from: Dialect, to: Dialect) -> Result<String, String> {
+        let (ltl, ap_map) = parse_in::<u64>(formula, from).unwrap();
+        print(&ltl, &ap_map, to)
+    }
+
+    #[test]
+    fn test_minimal_parentheses() {
+        for formula in [
+            "G (a -> F b)",
+            "a R b W !c",
+            "a U (b U c)",
+            "a -> b -> c",
+            "(a -> b) -> c",
+            "a <-> b <-> c",
+            "a & b | c & !d",
+            "(a | b) & X (c | true)",
+            "!(a & b) U F G c",
+            "X X door.open -> \"x>3\"",
+            "a -> a",
+            "a & false",
+        ] {
+            assert_eq!(
+                translate(formula, Dialect::Native, Dialect::Native).unwrap(),
+                formula
+            );
+        }
+        assert_eq!(
+            translate("((a) & ((b)))", Dialect::Native, Dialect::Native).unwrap(),
+            "a & b"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for dialect in [Dialect::Native, Dialect::Spot, Dialect::NuSMV] {
+            for formula in [
+                "G(a -> F b) & (c R !d)",
+                "(a U b) & c U d",
+                "a U b & c U d",
+                "(a <-> b) -> c <-> d",
+                "a -> (b <-> c) & d | e",
+                "G(a W (b W c)) | (a W b) W c",
+                "!X(a | b) U (0 R c)",
+                "b & !b | a",
+                "G a & !G a | b",
+                "a -> a",
+            ] {
+                let (ltl, ap_map) = parse_in::<u64>(formula, Dialect::Native).unwrap();
+                let printed = print(&ltl, &ap_map, dialect).unwrap();
+                let (reparsed, reparsed_map) = parse_in::<u64>(&printed, dialect).unwrap();
+                if formula.contains('W') && dialect == Dialect::NuSMV {
+                    assert_eq!(print(&reparsed, &reparsed_map, dialect).unwrap(), printed);
+                } else {
+                    assert_eq!(reparsed, ltl, "{formula} printed as {printed}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dialects() {
+        assert_eq!(
+            translate("[](req -> <>ack) && (a V b)", Dialect::Spot, Dialect::NuSMV).unwrap(),
+            "G (req -> F ack) & a V b"
+        );
+        assert_eq!(
+            translate("a & b U c", Dialect::Native, Dialect::Spot).unwrap(),
+            "(a & b) U c"
+        );
+        assert_eq!(
+            translate("a U b U c", Dialect::Spot, Dialect::Native).unwrap(),
+            "a U (b U c)"
+        );
+        assert_eq!(
+            translate("G(a W b) | 0", Dialect::Native, Dialect::NuSMV).unwrap(),
+            "G (a U b | G a) | FALSE"
+        );
+        assert_eq!(
+            translate("TRUE & O & p.x", Dialect::NuSMV, Dialect::Native).unwrap(),
+            "true & \"O\" & p.x"
+        );
+        assert_eq!(
+            translate("O & true", Dialect::Spot, Dialect::Spot).unwrap(),
+            "O & true"
+        );
+        assert_eq!(
+            translate("\"x>3\" U \"true\"", Dialect::Native, Dialect::Spot).unwrap(),
+            "\"x>3\" U \"true\""
+        );
+        assert_eq!(
+            translate("\"x>3\" U b", Dialect::Native, Dialect::NuSMV),
+            Err("x>3".to_string())
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Property specification patterns of Dwyer, Avrunin and Corbett, instantiated with their
+//! published LTL mappings:
+//!
+//! ```
+//! use model_checker::ap;
+//! use model_checker::patterns::{response, Scope};
+//!
+//! let formula = response(ap("req"), ap("grant"), Scope::Globally);
+//! assert_eq!(formula.to_string(), "G (req -> F grant)");
+//! ```
+
+use crate::Formula;
+
+/// Part of a run in which a pattern has to hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope {
+    Globally,
+    /// Up to the first state in which `r` holds.
+    Before(Formula),
+    /// From the first state in which `q` holds.
+    After(Formula),
+    /// From every state in which `q` holds up to the next state in which `r` holds, if there is
+    /// one.
+    Between(Formula, Formula),
+    /// Like [`Scope::Between`], but also when `r` never holds.
+    AfterUntil(Formula, Formula),
+}
+
+/// `q & !r`, the start of a between or after-until scope.
+fn opens(q: Formula, r: &Formula) -> Formula {
+    q & !r.clone()
+}
+
+/// `p` is false.
+pub fn absence(p: Formula, scope: Scope) -> Formula {
+    match scope {
+        Scope::Globally => (!p).always(),
+        Scope::Before(r) => r.clone().eventually().implies((!p).until(r)),
+        Scope::After(q) => q.implies((!p).always()).always(),
+        Scope::Between(q, r) => opens(q, &r)
+            .and(r.clone().eventually())
+            .implies((!p).until(r))
+            .always(),
+        Scope::AfterUntil(q, r) => opens(q, &r).implies((!p).weak_until(r)).always(),
+    }
+}
+
+/// `p` becomes true.
+pub fn existence(p: Formula, scope: Scope) -> Formula {
+    match scope {
+        Scope::Globally => p.eventually(),
+        Scope::Before(r) => (!r.clone()).weak_until(p & !r),
+        Scope::After(q) => (!q.clone()).always() | (q & p.eventually()).eventually(),
+        Scope::Between(q, r) => opens(q, &r)
+            .implies((!r.clone()).weak_until(p & !r))
+            .always(),
+        Scope::AfterUntil(q, r) => opens(q, &r).implies((!r.clone()).until(p & !r)).always(),
+    }
+}
+
+/// `p` is true.
+pub fn universality(p: Formula, scope: Scope) -> Formula {
+    match scope {
+        Scope::Globally => p.always(),
+        Scope::Before(r) => r.clone().eventually().implies(p.until(r)),
+        Scope::After(q) => q.implies(p.always()).always(),
+        Scope::Between(q, r) => opens(q, &r)
+            .and(r.clone().eventually())
+            .implies(p.until(r))
+            .always(),
+        Scope::AfterUntil(q, r) => opens(q, &r).implies(p.weak_until(r)).always(),
+    }
+}
+
+/// `s` precedes `p`: `p` only holds after `s` held.
+pub fn precedence(s: Formula, p: Formula, scope: Scope) -> Formula {
+    match scope {
+        Scope::Globally => (!p).weak_until(s),
+        Scope::Before(r) => r.clone().eventually().implies((!p).until(s | r)),
+        Scope::After(q) => (!q.clone()).always() | (q & (!p).weak_until(s)).eventually(),
+        Scope::Between(q, r) => opens(q, &r)
+            .and(r.clone().eventually())
+            .implies((!p).until(s | r))
+            .always(),
+        Scope::AfterUntil(q, r) => opens(q, &r).implies((!p).weak_until(s | r)).always(),
+    }
+}
+
+/// `s` responds to `p`: every `p` is followed by `s`.
+pub fn response(p: Formula, s: Formula, scope: Scope) -> Formula {
+    // `p -> (!r U (s & !r))`, a response before `r`.
+    let responds_before =
+        |p: Formula, s: Formula, r: &Formula| p.implies((!r.clone()).until(s & !r.clone()));
+    match scope {
+        Scope::Globally => p.implies(s.eventually()).always(),
+        Scope::Before(r) => r
+            .clone()
+            .eventually()
+            .implies(responds_before(p, s, &r).until(r)),
+        Scope::After(q) => q.implies(p.implies(s.eventually()).always()).always(),
+        Scope::Between(q, r) => opens(q, &r)
+            .and(r.clone().eventually())
+            .implies(responds_before(p, s, &r).until(r))
+            .always(),
+        Scope::AfterUntil(q, r) => opens(q, &r)
+            .implies(responds_before(p, s, &r).weak_until(r))
+            .always(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ap;
+
+    fn scopes() -> [Scope; 5] {
+        [
+            Scope::Globally,
+            Scope::Before(ap("r")),
+            Scope::After(ap("q")),
+            Scope::Between(ap("q"), ap("r")),
+            Scope::AfterUntil(ap("q"), ap("r")),
+        ]
+    }
+
+    fn assert_mappings(pattern: impl Fn(Scope) -> Formula, mappings: [&str; 5]) {
+        for (scope, mapping) in scopes().into_iter().zip(mappings) {
+            assert_eq!(
+                pattern(scope).to_string(),
+                Formula::parse(mapping).unwrap().to_string(),
+                "{}",
+                mapping
+            );
+        }
+    }
+
+    #[test]
+    fn test_mappings() {
+        assert_mappings(
+            |scope| absence(ap("p"), scope),
+            [
+                "G !p",
+                "F r -> (!p U r)",
+                "G (q -> G !p)",
+                "G (q & !r & F r -> (!p U r))",
+                "G (q & !r -> (!p W r))",
+            ],
+        );
+        assert_mappings(
+            |scope| existence(ap("p"), scope),
+            [
+                "F p",
+                "!r W (p & !r)",
+                "G !q | F (q & F p)",
+                "G (q & !r -> (!r W (p & !r)))",
+                "G (q & !r -> (!r U (p & !r)))",
+            ],
+        );
+        assert_mappings(
+            |scope| universality(ap("p"), scope),
+            [
+                "G p",
+                "F r -> (p U r)",
+                "G (q -> G p)",
+                "G (q & !r & F r -> (p U r))",
+                "G (q & !r -> (p W r))",
+            ],
+        );
+        assert_mappings(
+            |scope| precedence(ap("s"), ap("p"), scope),
+            [
+                "!p W s",
+                "F r -> (!p U (s | r))",
+                "G !q | F (q & (!p W s))",
+                "G (q & !r & F r -> (!p U (s | r)))",
+                "G (q & !r -> (!p W (s | r)))",
+            ],
+        );
+        assert_mappings(
+            |scope| response(ap("p"), ap("s"), scope),
+            [
+                "G (p -> F s)",
+                "F r -> ((p -> (!r U (s & !r))) U r)",
+                "G (q -> G (p -> F s))",
+                "G (q & !r & F r -> ((p -> (!r U (s & !r))) U r))",
+                "G (q & !r -> ((p -> (!r U (s & !r))) W r))",
+            ],
+        );
+    }
+}
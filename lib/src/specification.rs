@@ -0,0 +1,468 @@
+use crate::parsing::lexer::{identifier_end, starts_name};
+use crate::parsing::Dialect;
+use crate::specification::specification_error::{SpecificationError, SpecificationErrorKind};
+use crate::{
+    ltl_model_check_formula, Counterexample, Formula, KripkeBuilder, ModelCheckingError,
+    ModelCheckingErrorKind,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub mod specification_error;
+
+type CheckResult<Id> = Result<Option<Counterexample<Id>>, ModelCheckingError<Id>>;
+
+/// Named definition, whose parameters are replaced by the arguments it is used with.
+struct Definition {
+    /// Number of definitions before this one, which are the ones its body can use.
+    index: usize,
+    parameters: Vec<String>,
+    /// Formula as written, the definitions it uses are replaced when it is used.
+    body: String,
+}
+
+/// Properties of a specification file, which may share definitions:
+///
+/// ```text
+/// // Comments start with two slashes.
+/// let idle = !req & !busy;
+/// let responds(p, q) = G(p -> F q);
+/// property progress: responds(req, grant);
+/// property quiet: F G idle;
+/// ```
+///
+/// A definition can be used in the properties and definitions after it and is replaced by its
+/// formula in parentheses. Propositions are names that are not defined.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specification {
+    properties: Vec<(String, Formula)>,
+}
+
+impl Specification {
+    pub fn parse(text: &str) -> Result<Specification, SpecificationError> {
+        let mut parser = Parser {
+            text,
+            chars: without_comments(text),
+            i: 0,
+            definitions: HashMap::new(),
+        };
+        let mut properties = Vec::<(String, Formula)>::new();
+        loop {
+            parser.skip_whitespace();
+            if parser.i == parser.chars.len() {
+                break;
+            }
+            let start = parser.i;
+            if parser.keyword("let") {
+                let name = parser.name()?;
+                let mut parameters = vec![];
+                if parser.eat('(') {
+                    loop {
+                        parameters.push(parser.name()?);
+                        if !parser.eat(',') {
+                            break;
+                        }
+                    }
+                    parser.expect(')')?;
+                }
+                parser.expect('=')?;
+                let (at, body) = parser.body()?;
+                let unbound = parameters
+                    .iter()
+                    .map(|parameter| (parameter.as_str(), parameter.clone()))
+                    .collect();
+                let expanded = parser
+                    .expand(&body, parser.definitions.len(), &unbound)
+                    .map_err(|kind| parser.error(kind, at))?;
+                // Definitions such as `true` need no propositions.
+                if let Err(error) = crate::parsing::parse::<()>(&expanded) {
+                    if let ModelCheckingErrorKind::FormulaSytaxError(error) = error.kind() {
+                        let kind = SpecificationErrorKind::FormulaSyntaxError(name, error.clone());
+                        return Err(parser.error(kind, at));
+                    }
+                }
+                if parser.definitions.contains_key(&name) {
+                    let kind = SpecificationErrorKind::DuplicateName(name);
+                    return Err(parser.error(kind, start));
+                }
+                let index = parser.definitions.len();
+                parser.definitions.insert(
+                    name,
+                    Definition {
+                        index,
+                        parameters,
+                        body,
+                    },
+                );
+            } else if parser.keyword("property") {
+                let name = parser.name()?;
+                parser.expect(':')?;
+                let (at, body) = parser.body()?;
+                let body = parser
+                    .expand(&body, parser.definitions.len(), &HashMap::new())
+                    .map_err(|kind| parser.error(kind, at))?;
+                let formula = Formula::parse(&body).map_err(|error| {
+                    let kind = match error.kind() {
+                        ModelCheckingErrorKind::FormulaSytaxError(error) => {
+                            SpecificationErrorKind::FormulaSyntaxError(name.clone(), error.clone())
+                        }
+                        _ => SpecificationErrorKind::FormulaNoAPs(name.clone()),
+                    };
+                    parser.error(kind, at)
+                })?;
+                if properties.iter().any(|(other, _)| *other == name) {
+                    let kind = SpecificationErrorKind::DuplicateName(name);
+                    return Err(parser.error(kind, start));
+                }
+                properties.push((name, formula));
+            } else {
+                return Err(parser.error(SpecificationErrorKind::UnexpectedToken, start));
+            }
+        }
+        Ok(Specification { properties })
+    }
+
+    /// Properties by name, in the order of the specification.
+    pub fn properties(&self) -> &[(String, Formula)] {
+        &self.properties
+    }
+
+    /// Checks every property on its own and returns the results by property name.
+    pub fn check<Id: Hash + Eq + Clone>(
+        &self,
+        ks: &KripkeBuilder<Id>,
+    ) -> Vec<(&str, CheckResult<Id>)> {
+        self.properties
+            .iter()
+            .map(|(name, formula)| {
+                let result = ltl_model_check_formula(ks.clone(), formula.ltl(), formula.ap_map());
+                (name.as_str(), result)
+            })
+            .collect()
+    }
+}
+
+/// Characters of `text` with comments replaced by spaces, so that offsets stay the same.
+fn without_comments(text: &str) -> Vec<char> {
+    let mut chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '"' {
+            i = quote_end(&chars, i);
+        } else if chars[i] == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                chars[i] = ' ';
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    chars
+}
+
+/// Offset after the quoted proposition starting at `start`.
+fn quote_end(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != '"' {
+        i += if chars[i] == '\\' { 2 } else { 1 };
+    }
+    (i + 1).min(chars.len())
+}
+
+/// Calls `replace` for every name in `text` outside of quotes, with the offset after the name.
+/// A name is replaced by the returned text, `replace` may advance the offset to replace more.
+fn replace_names(
+    text: &str,
+    mut replace: impl FnMut(&str, &[char], &mut usize) -> Result<Option<String>, SpecificationErrorKind>,
+) -> Result<String, SpecificationErrorKind> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '"' {
+            let end = quote_end(&chars, i);
+            result.extend(&chars[i..end]);
+            i = end;
+        } else if starts_name(chars[i], Dialect::Native) {
+            let start = i;
+            i = identifier_end(&chars, i, Dialect::Native);
+            let name: String = chars[start..i].iter().collect();
+            match replace(&name, &chars, &mut i)? {
+                Some(replacement) => result.push_str(&replacement),
+                None => result.push_str(&name),
+            }
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+    Ok(result)
+}
+
+/// Arguments in parentheses starting at `*i`, which are separated by commas outside of nested
+/// parentheses. Advances `*i` after the closing parenthesis.
+fn arguments(
+    name: &str,
+    chars: &[char],
+    i: &mut usize,
+) -> Result<Vec<String>, SpecificationErrorKind> {
+    while chars.get(*i).is_some_and(|c| c.is_whitespace()) {
+        *i += 1;
+    }
+    if chars.get(*i) != Some(&'(') {
+        return Err(SpecificationErrorKind::MissingArguments(name.to_string()));
+    }
+    *i += 1;
+    let mut arguments = vec![String::new()];
+    let mut depth = 0;
+    loop {
+        match chars.get(*i) {
+            None => return Err(SpecificationErrorKind::UnexpectedEnd),
+            Some('"') => {
+                let end = quote_end(chars, *i);
+                arguments.last_mut().unwrap().extend(&chars[*i..end]);
+                *i = end;
+                continue;
+            }
+            Some(')') if depth == 0 => break,
+            Some(',') if depth == 0 => arguments.push(String::new()),
+            Some(c) => {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                arguments.last_mut().unwrap().push(*c);
+            }
+        }
+        *i += 1;
+    }
+    *i += 1;
+    Ok(arguments)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    chars: Vec<char>,
+    i: usize,
+    definitions: HashMap<String, Definition>,
+}
+
+impl Parser<'_> {
+    fn error(&self, kind: SpecificationErrorKind, at: usize) -> SpecificationError {
+        SpecificationError::new(kind, self.text, at)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.i).is_some_and(|c| c.is_whitespace()) {
+            self.i += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.chars.get(self.i) == Some(&c) {
+            self.i += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), SpecificationError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn unexpected(&self) -> SpecificationError {
+        if self.i == self.chars.len() {
+            self.error(SpecificationErrorKind::UnexpectedEnd, self.i)
+        } else {
+            self.error(SpecificationErrorKind::UnexpectedToken, self.i)
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let end = self.i + keyword.chars().count();
+        let matches = self.chars[self.i..]
+            .iter()
+            .copied()
+            .take(end - self.i)
+            .eq(keyword.chars())
+            && identifier_end(&self.chars, self.i, Dialect::Native) == end;
+        if matches {
+            self.i = end;
+        }
+        matches
+    }
+
+    fn name(&mut self) -> Result<String, SpecificationError> {
+        self.skip_whitespace();
+        if !self
+            .chars
+            .get(self.i)
+            .is_some_and(|c| starts_name(*c, Dialect::Native))
+        {
+            return Err(self.unexpected());
+        }
+        let start = self.i;
+        self.i = identifier_end(&self.chars, self.i, Dialect::Native);
+        Ok(self.chars[start..self.i].iter().collect())
+    }
+
+    /// Formula up to the next `;` outside of quotes, and the offset it starts at.
+    fn body(&mut self) -> Result<(usize, String), SpecificationError> {
+        self.skip_whitespace();
+        let start = self.i;
+        while self.i < self.chars.len() && self.chars[self.i] != ';' {
+            if self.chars[self.i] == '"' {
+                self.i = quote_end(&self.chars, self.i);
+            } else {
+                self.i += 1;
+            }
+        }
+        if self.i == self.chars.len() {
+            return Err(self.error(SpecificationErrorKind::UnexpectedEnd, self.i));
+        }
+        self.i += 1;
+        Ok((start, self.chars[start..self.i - 1].iter().collect()))
+    }
+
+    /// Replaces the first `scope` definitions where they are used in `text`, and the parameters
+    /// by their expanded `values`. Parameters hide definitions of the same name.
+    fn expand(
+        &self,
+        text: &str,
+        scope: usize,
+        values: &HashMap<&str, String>,
+    ) -> Result<String, SpecificationErrorKind> {
+        replace_names(text, |name, chars, i| {
+            if let Some(value) = values.get(name) {
+                return Ok(Some(format!("({})", value)));
+            }
+            let Some(definition) = self
+                .definitions
+                .get(name)
+                .filter(|definition| definition.index < scope)
+            else {
+                return Ok(None);
+            };
+            let arguments = if definition.parameters.is_empty() {
+                vec![]
+            } else {
+                arguments(name, chars, i)?
+            };
+            if arguments.len() != definition.parameters.len() {
+                return Err(SpecificationErrorKind::WrongAmountOfArguments {
+                    name: name.to_string(),
+                    expected: definition.parameters.len(),
+                    found: arguments.len(),
+                });
+            }
+            // Arguments are expanded where the definition is used, its body where it is defined.
+            let arguments: HashMap<&str, String> = definition
+                .parameters
+                .iter()
+                .map(String::as_str)
+                .zip(arguments)
+                .map(|(parameter, value)| Ok((parameter, self.expand(&value, scope, values)?)))
+                .collect::<Result<_, SpecificationErrorKind>>()?;
+            let body = self.expand(&definition.body, definition.index, &arguments)?;
+            Ok(Some(format!("({})", body)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn property(specification: &Specification, name: &str) -> String {
+        specification
+            .properties()
+            .iter()
+            .find(|(other, _)| other == name)
+            .unwrap()
+            .1
+            .to_string()
+    }
+
+    #[test]
+    fn test_expansion() {
+        let specification = Specification::parse(
+            "// Definitions
+            let idle = !req & !busy;
+            let responds(p, q) = G(p -> F q);
+            let eventually_idle(p) = responds(p, idle); // uses both
+            property progress: responds(req, grant | \"x;y\");
+            property quiet: eventually_idle(req & X busy);
+            property nested: responds(responds(a, b), idle);",
+        )
+        .unwrap();
+        assert_eq!(specification.properties().len(), 3);
+        assert_eq!(
+            property(&specification, "progress"),
+            "G (req -> F (grant | \"x;y\"))"
+        );
+        assert_eq!(
+            property(&specification, "quiet"),
+            "G (req & X busy -> F (!req & !busy))"
+        );
+        assert_eq!(
+            property(&specification, "nested"),
+            "G (G (a -> F b) -> F (!req & !busy))"
+        );
+    }
+
+    #[test]
+    fn test_scopes() {
+        let specification = Specification::parse(
+            "let idle = !p;
+            let f(p) = idle & p;
+            let g = h;
+            let h = a;
+            property captured: f(q);
+            property later: g & h;",
+        )
+        .unwrap();
+        assert_eq!(property(&specification, "captured"), "!p & q");
+        assert_eq!(property(&specification, "later"), "h & a");
+    }
+
+    #[test]
+    fn test_errors() {
+        let kind = |text: &str| Specification::parse(text).unwrap_err().kind().clone();
+        assert_eq!(
+            kind("let a = b;\nlet a = c;"),
+            SpecificationErrorKind::DuplicateName("a".to_string())
+        );
+        assert_eq!(
+            kind("let r(p) = F p; property x: r;"),
+            SpecificationErrorKind::MissingArguments("r".to_string())
+        );
+        assert_eq!(
+            kind("let r(p) = F p; property x: r(a, b);"),
+            SpecificationErrorKind::WrongAmountOfArguments {
+                name: "r".to_string(),
+                expected: 1,
+                found: 2
+            }
+        );
+        assert!(matches!(
+            kind("let a = b &;"),
+            SpecificationErrorKind::FormulaSyntaxError(..)
+        ));
+        assert_eq!(
+            kind("property x: true;"),
+            SpecificationErrorKind::FormulaNoAPs("x".to_string())
+        );
+        assert_eq!(kind("property x: a"), SpecificationErrorKind::UnexpectedEnd);
+        let error = Specification::parse("let a = b;\n  check a;").unwrap_err();
+        assert_eq!(error.kind(), &SpecificationErrorKind::UnexpectedToken);
+        assert_eq!(error.position(), (2, 3));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::parsing::parsing_error::ParsingError;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpecificationErrorKind {
+    /// A statement is neither a `let` definition nor a `property`.
+    UnexpectedToken,
+    UnexpectedEnd,
+    DuplicateName(String),
+    /// A definition with parameters is used without arguments.
+    MissingArguments(String),
+    WrongAmountOfArguments {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The formula of the named definition or property cannot be parsed once the definitions
+    /// it uses are replaced.
+    FormulaSyntaxError(String, ParsingError),
+    FormulaNoAPs(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpecificationError {
+    kind: SpecificationErrorKind,
+    line: usize,
+    column: usize,
+}
+
+impl SpecificationError {
+    /// Creates an error for the character offset `at` in `text`.
+    pub fn new(kind: SpecificationErrorKind, text: &str, at: usize) -> SpecificationError {
+        let before: Vec<char> = text.chars().take(at).collect();
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+        SpecificationError { kind, line, column }
+    }
+
+    pub fn kind(&self) -> &SpecificationErrorKind {
+        &self.kind
+    }
+
+    /// Line and column (both starting at 1) of the error.
+    pub fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+}
+
+impl Error for SpecificationError {}
+
+impl Display for SpecificationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            SpecificationErrorKind::UnexpectedToken => write!(f, "Expected `let` or `property`")?,
+            SpecificationErrorKind::UnexpectedEnd => write!(f, "Unexpected end of specification")?,
+            SpecificationErrorKind::DuplicateName(name) => {
+                write!(f, "'{}' is declared more than once", name)?
+            }
+            SpecificationErrorKind::MissingArguments(name) => {
+                write!(f, "'{}' is used without arguments", name)?
+            }
+            SpecificationErrorKind::WrongAmountOfArguments {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "'{}' takes {} arguments, but {} are given",
+                name, expected, found
+            )?,
+            SpecificationErrorKind::FormulaSyntaxError(name, error) => {
+                write!(f, "Formula of '{}': {}", name, error)?
+            }
+            SpecificationErrorKind::FormulaNoAPs(name) => {
+                write!(f, "Formula of '{}' has no atomic propositions", name)?
+            }
+        }
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
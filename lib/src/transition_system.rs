@@ -0,0 +1,250 @@
+use crate::buechi::ltl_to_buechi::ltl_to_reduced_büchi;
+use crate::buechi::Büchi;
+use crate::kripke::symbol_of_labels;
+use crate::parsing::LTLFormula;
+use crate::{
+    parsing, Counterexample, DeadlockPolicy, KripkeBuilder, ModelCheckingError,
+    ModelCheckingErrorKind,
+};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Model whose states are only discovered while checking, e.g. a simulator or a state machine.
+///
+/// The state space is never built as a whole: only states reachable from the initial states
+/// are looked at, and the search stops as soon as a counterexample is found.
+pub trait TransitionSystem {
+    type State: Hash + Eq + Clone;
+
+    fn initial_states(&self) -> Vec<Self::State>;
+
+    fn successors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// Atomic propositions that hold in `state`.
+    fn labels(&self, state: &Self::State) -> Vec<String>;
+
+    /// Successors together with the action of the transition leading to them, see
+    /// [`KripkeBuilder::add_labelled_transition`]. By default, transitions have no actions.
+    fn labelled_successors(&self, state: &Self::State) -> Vec<(Self::State, Option<String>)> {
+        self.successors(state)
+            .into_iter()
+            .map(|successor| (successor, None))
+            .collect()
+    }
+
+    fn deadlock_policy(&self) -> DeadlockPolicy {
+        DeadlockPolicy::default()
+    }
+}
+
+impl<Id: Hash + Eq + Clone> TransitionSystem for KripkeBuilder<Id> {
+    type State = Id;
+
+    fn initial_states(&self) -> Vec<Id> {
+        self.states()
+            .filter(|(_, _, start)| *start)
+            .map(|(id, _, _)| id.clone())
+            .collect()
+    }
+
+    fn successors(&self, state: &Id) -> Vec<Id> {
+        let mut successors = Vec::<Id>::new();
+        for (successor, _) in KripkeBuilder::successors(self, state) {
+            if !successors.contains(successor) {
+                successors.push(successor.clone());
+            }
+        }
+        successors
+    }
+
+    fn labels(&self, state: &Id) -> Vec<String> {
+        self.aps(state).map(|aps| aps.to_vec()).unwrap_or_default()
+    }
+
+    fn labelled_successors(&self, state: &Id) -> Vec<(Id, Option<String>)> {
+        KripkeBuilder::successors(self, state)
+            .map(|(successor, action)| (successor.clone(), action.map(str::to_string)))
+            .collect()
+    }
+
+    fn deadlock_policy(&self) -> DeadlockPolicy {
+        KripkeBuilder::deadlock_policy(self)
+    }
+}
+
+/// State of the product of a transition system and the formula automaton.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Node<S> {
+    Initial,
+    DeadSink,
+    State(S),
+}
+
+type ProductState<S> = (Node<S>, u64);
+
+struct Explorer<'a, T: TransitionSystem> {
+    system: &'a T,
+    büchi: Büchi<(u64, u8)>,
+    ap_map: HashMap<String, u8>,
+    alive: u64,
+}
+
+impl<T: TransitionSystem> Explorer<'_, T> {
+    fn symbol(&self, state: &T::State, action: Option<&String>) -> u64 {
+        let labels = self.system.labels(state);
+        let labels = labels.iter().chain(action).map(String::as_str);
+        symbol_of_labels(labels, &self.ap_map) | self.alive
+    }
+
+    fn successors(
+        &self,
+        (node, q): &ProductState<T::State>,
+    ) -> Result<Vec<ProductState<T::State>>, ModelCheckingError<T::State>> {
+        let model_successors: Vec<(Node<T::State>, u64)> = match node {
+            Node::Initial => self
+                .system
+                .initial_states()
+                .into_iter()
+                .map(|state| {
+                    let symbol = self.symbol(&state, None);
+                    (Node::State(state), symbol)
+                })
+                .collect(),
+            Node::DeadSink => vec![(Node::DeadSink, 0)],
+            Node::State(state) => {
+                let successors = self.system.labelled_successors(state);
+                if successors.is_empty() {
+                    match self.system.deadlock_policy() {
+                        DeadlockPolicy::Error => {
+                            return Err(ModelCheckingError::new(ModelCheckingErrorKind::Deadlock(
+                                state.clone(),
+                            )));
+                        }
+                        DeadlockPolicy::Stutter => {
+                            vec![(Node::State(state.clone()), self.symbol(state, None))]
+                        }
+                        DeadlockPolicy::DeadSink | DeadlockPolicy::Finite => {
+                            vec![(Node::DeadSink, 0)]
+                        }
+                    }
+                } else {
+                    successors
+                        .into_iter()
+                        .map(|(successor, action)| {
+                            let symbol = self.symbol(&successor, action.as_ref());
+                            (Node::State(successor), symbol)
+                        })
+                        .collect()
+                }
+            }
+        };
+        Ok(model_successors
+            .into_iter()
+            .flat_map(|(node, symbol)| {
+                self.büchi
+                    .successors_with_symbol(*q, symbol)
+                    .map(move |next| (node.clone(), next))
+            })
+            .collect())
+    }
+
+    /// Nested depth-first search for an accepting cycle, without recursion. Returns the path
+    /// from the initial state and the index at which the cycle starts.
+    #[allow(clippy::type_complexity)]
+    fn find_lasso(
+        &self,
+    ) -> Result<Option<(Vec<ProductState<T::State>>, usize)>, ModelCheckingError<T::State>> {
+        let start = (Node::Initial, self.büchi.start_state());
+        let mut outer_visited = HashSet::<ProductState<T::State>>::new();
+        let mut on_stack = HashMap::<ProductState<T::State>, usize>::new();
+        let mut inner_visited = HashSet::<ProductState<T::State>>::new();
+        let mut stack = vec![(start.clone(), self.successors(&start)?, 0)];
+        outer_visited.insert(start.clone());
+        on_stack.insert(start, 0);
+
+        while let Some((state, successors, next)) = stack.last_mut() {
+            if *next < successors.len() {
+                let successor = successors[*next].clone();
+                *next += 1;
+                if outer_visited.insert(successor.clone()) {
+                    let successors = self.successors(&successor)?;
+                    on_stack.insert(successor.clone(), stack.len());
+                    stack.push((successor, successors, 0));
+                }
+                continue;
+            }
+
+            if self.büchi.is_accepting(state.1) {
+                // States on the outer stack reach the seed, so reaching one of them closes a
+                // cycle through the seed.
+                let mut inner_stack = vec![(state.clone(), successors.clone(), 0)];
+                while let Some((_, successors, next)) = inner_stack.last_mut() {
+                    if *next == successors.len() {
+                        inner_stack.pop();
+                        continue;
+                    }
+                    let successor = successors[*next].clone();
+                    *next += 1;
+                    if let Some(cycle_start) = on_stack.get(&successor) {
+                        let mut path: Vec<ProductState<T::State>> =
+                            stack.into_iter().map(|(state, _, _)| state).collect();
+                        path.extend(inner_stack.into_iter().skip(1).map(|(state, _, _)| state));
+                        return Ok(Some((path, *cycle_start)));
+                    }
+                    if inner_visited.insert(successor.clone()) {
+                        let successors = self.successors(&successor)?;
+                        inner_stack.push((successor, successors, 0));
+                    }
+                }
+            }
+
+            let (state, _, _) = stack.pop().unwrap();
+            on_stack.remove(&state);
+        }
+        Ok(None)
+    }
+}
+
+/// Checks `formula` on a transition system by exploring it together with the formula
+/// automaton, see [`TransitionSystem`]. Unlike [`ltl_model_check`](crate::ltl_model_check),
+/// the model is not validated beforehand; with [`DeadlockPolicy::Error`], only deadlocks that
+/// are reached are reported.
+pub fn ltl_model_check_system<T: TransitionSystem>(
+    system: &T,
+    formula: &str,
+) -> Result<Option<Counterexample<T::State>>, ModelCheckingError<T::State>> {
+    let (ltl, ap_map) = parsing::parse(formula)?;
+    let finite = system.deadlock_policy() == DeadlockPolicy::Finite;
+    let ltl = if finite {
+        ltl.to_finite_semantics(ap_map.len() as u8)
+    } else {
+        ltl
+    };
+    let (büchi, _) =
+        ltl_to_reduced_büchi(&LTLFormula::not(ltl), ap_map.len() as u8 + u8::from(finite));
+    let alive = if finite { 1 << ap_map.len() } else { 0 };
+    let explorer = Explorer {
+        system,
+        büchi,
+        ap_map,
+        alive,
+    };
+
+    let Some((path, cycle_start)) = explorer.find_lasso()? else {
+        return Ok(None);
+    };
+    let model_states = |states: &[ProductState<T::State>]| -> Vec<Option<T::State>> {
+        states
+            .iter()
+            .map(|(node, _)| match node {
+                Node::State(state) => Some(state.clone()),
+                Node::Initial | Node::DeadSink => None,
+            })
+            .collect()
+    };
+    Ok(Some(Counterexample::from_path(
+        model_states(&path[..cycle_start]),
+        model_states(&path[cycle_start..]),
+        |state| system.successors(state).is_empty(),
+    )))
+}
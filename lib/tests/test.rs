@@ -17,4 +17,878 @@ fn test() {
             .unwrap()
             .is_some()
     );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "(a|b) W 0")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "a W 0")
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn test_multiple_acceptance_sets() {
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, true);
+    kripke_builder.add_state(vec!["b".to_string()], 1, false);
+    kripke_builder.add_state(vec![], 2, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(1, 0);
+    kripke_builder.add_transition(1, 2);
+    kripke_builder.add_transition(2, 2);
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "(a U b) & X b")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "G F a & G F b")
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "F G !a | G F a & G F b")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_actions() {
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["idle".to_string()], 0, true);
+    kripke_builder.add_state(vec!["waiting".to_string()], 1, false);
+    kripke_builder.add_labelled_transition(0, 1, "send".to_string());
+    kripke_builder.add_labelled_transition(1, 0, "ack".to_string());
+    kripke_builder.add_labelled_transition(1, 1, "retry".to_string());
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "G(!send | waiting)")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "G(!send | F ack)")
+            .unwrap()
+            .is_some()
+    );
+    assert!(model_checker::ltl_model_check(
+        kripke_builder.clone(),
+        "!G F !retry | G(!send | F ack)"
+    )
+    .unwrap()
+    .is_none());
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "!send & X send")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_guarded_program() {
+    let program = "
+        var x: 0..4 init 0;
+        var done: bool;
+        label high = x > 2;
+        process counter {
+            [inc] x < 4 & !done -> x := x + 1;
+            [finish] x = 4 -> done := true;
+        }
+        process reset {
+            [reset] done -> x := 0, done := false;
+        }
+    ";
+    let (model, result) =
+        model_checker::ltl_model_check_program(program, "G(!done | high)").unwrap();
+    assert_eq!(model.amount_states(), 6);
+    assert!(result.is_none());
+    let (_, result) = model_checker::ltl_model_check_program(program, "G F finish").unwrap();
+    assert!(result.is_none());
+    let (model, result) = model_checker::ltl_model_check_program(program, "G !high").unwrap();
+    assert!(result
+        .unwrap()
+        .states()
+        .any(|state| model.format_state(*state).as_deref() == Some("x = 3, done = false")));
+    assert!(model_checker::ltl_model_check_program(program, "G F typo").is_err());
+
+    // Expressions in quotes are propositions like labels.
+    let (_, result) =
+        model_checker::ltl_model_check_program(program, "G(!done | \"x > 2\")").unwrap();
+    assert!(result.is_none());
+    let (model, result) =
+        model_checker::ltl_model_check_program(program, "G(\"x >= 1\" -> done)").unwrap();
+    assert!(result
+        .unwrap()
+        .states()
+        .any(|state| model.format_state(*state).as_deref() == Some("x = 1, done = false")));
+}
+
+#[test]
+fn test_validation() {
+    use model_checker::ModelCheckingErrorKind as K;
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, false);
+    kripke_builder.add_state(vec![], 1, false);
+    kripke_builder.add_state(vec![], 1, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(2, 0);
+    kripke_builder.add_transition(1, 3);
+    let errors = kripke_builder.validate();
+    let kinds: Vec<&K> = errors.iter().map(|error| error.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &K::DuplicateStateId(1),
+            &K::ModelNoStart,
+            &K::DanglingTransitionSource { from: 2, to: 0 },
+            &K::DanglingTransitionTarget { from: 1, to: 3 },
+        ]
+    );
+    assert_eq!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "G a")
+            .unwrap_err()
+            .kind(),
+        &K::DuplicateStateId(1)
+    );
+    assert_eq!(
+        model_checker::ModelCheckingError::new(K::DanglingTransitionTarget { from: 1, to: 3 })
+            .to_string(),
+        "Transition from 1 to 3 ends at unknown state 3"
+    );
+}
+
+#[test]
+fn test_lint() {
+    use model_checker::LintWarningKind as K;
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["ready".to_string()], 0, true);
+    kripke_builder.add_state(vec!["busy".to_string()], 1, false);
+    kripke_builder.add_state(vec!["idle".to_string()], 2, false);
+    kripke_builder.add_labelled_transition(0, 1, "start".to_string());
+    kripke_builder.add_transition(2, 0);
+    let warnings = model_checker::lint(&kripke_builder, "G(raedy | start | done)").unwrap();
+    let kinds: Vec<&K> = warnings.iter().map(|warning| warning.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &K::Deadlock(1),
+            &K::UnreachableState(2),
+            &K::UnknownFormulaProposition {
+                name: "done".to_string(),
+                suggestion: None
+            },
+            &K::UnknownFormulaProposition {
+                name: "raedy".to_string(),
+                suggestion: Some("ready".to_string())
+            },
+            &K::UnusedModelProposition("busy".to_string()),
+            &K::UnusedModelProposition("idle".to_string()),
+            &K::UnusedModelProposition("ready".to_string()),
+        ]
+    );
+    assert_eq!(
+        warnings[3].to_string(),
+        "'raedy' does not occur in the model, did you mean 'ready'?"
+    );
+}
+
+#[test]
+fn test_deadlock_policies() {
+    use model_checker::{DeadlockPolicy, ModelCheckingErrorKind};
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, true);
+    kripke_builder.add_state(vec!["a".to_string(), "b".to_string()], 1, false);
+    kripke_builder.add_transition(0, 1);
+    let check = |policy: DeadlockPolicy, formula: &str| {
+        let mut kripke_builder = kripke_builder.clone();
+        kripke_builder.set_deadlock_policy(policy);
+        model_checker::ltl_model_check(kripke_builder, formula)
+    };
+
+    assert_eq!(
+        check(DeadlockPolicy::Error, "G a").unwrap_err().kind(),
+        &ModelCheckingErrorKind::Deadlock(1)
+    );
+
+    let counterexample = check(DeadlockPolicy::DeadSink, "G a").unwrap().unwrap();
+    assert_eq!(counterexample.prefix(), &[0, 1]);
+    assert!(counterexample.cycle().is_empty());
+    assert_eq!(counterexample.deadlock(), Some(&1));
+    assert_eq!(counterexample.to_string(), "0 -> 1 (deadlock in state 1)");
+
+    assert!(check(DeadlockPolicy::Stutter, "G a & F G b")
+        .unwrap()
+        .is_none());
+    let counterexample = check(DeadlockPolicy::Stutter, "G F !b").unwrap().unwrap();
+    assert_eq!(counterexample.cycle(), &[1]);
+    assert_eq!(counterexample.deadlock(), Some(&1));
+
+    assert!(check(DeadlockPolicy::Finite, "G a & F b")
+        .unwrap()
+        .is_none());
+    assert!(check(DeadlockPolicy::Finite, "a W 0").unwrap().is_none());
+    assert!(check(DeadlockPolicy::Finite, "F(b & !X 1)")
+        .unwrap()
+        .is_none());
+    assert!(check(DeadlockPolicy::Finite, "X X a").unwrap().is_some());
+    assert!(check(DeadlockPolicy::Finite, "a U !a").unwrap().is_some());
+}
+
+#[test]
+fn test_generic_ids() {
+    let mut kripke_builder = KripkeBuilder::<&str>::new();
+    kripke_builder.add_state(vec!["green".to_string()], "go", true);
+    kripke_builder.add_state(vec!["red".to_string()], "stop", false);
+    kripke_builder.add_transition("go", "stop");
+    kripke_builder.add_transition("stop", "go");
+    let counterexample = model_checker::ltl_model_check(kripke_builder, "G green")
+        .unwrap()
+        .unwrap();
+    assert!(counterexample.states().any(|state| *state == "stop"));
+
+    // Ids reserved for internal states in earlier versions are ordinary ids.
+    let mut kripke_builder = KripkeBuilder::<u64>::new();
+    kripke_builder.add_state(vec!["a".to_string()], u64::MAX, true);
+    kripke_builder.add_state(vec![], u64::MAX - 1, false);
+    kripke_builder.add_transition(u64::MAX, u64::MAX - 1);
+    let counterexample = model_checker::ltl_model_check(kripke_builder, "G a")
+        .unwrap()
+        .unwrap();
+    assert_eq!(counterexample.prefix(), &[u64::MAX, u64::MAX - 1]);
+    assert_eq!(counterexample.deadlock(), Some(&(u64::MAX - 1)));
+
+    let mut kripke_builder = KripkeBuilder::<(u8, bool)>::new();
+    kripke_builder.add_state(vec![], (0, false), true);
+    kripke_builder.add_transition((0, false), (1, true));
+    assert_eq!(
+        kripke_builder.validate()[0].kind(),
+        &model_checker::ModelCheckingErrorKind::DanglingTransitionTarget {
+            from: (0, false),
+            to: (1, true)
+        }
+    );
+}
+
+#[test]
+fn test_editing() {
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, true);
+    kripke_builder.add_state(vec![], 1, false);
+    kripke_builder.add_state(vec![], 2, false);
+    assert!(kripke_builder.add_transition(0, 1));
+    assert!(!kripke_builder.add_transition(0, 1));
+    assert!(kripke_builder.add_labelled_transition(0, 1, "go".to_string()));
+    assert!(kripke_builder.add_transition(1, 2));
+    assert!(kripke_builder.add_transition(2, 0));
+    assert_eq!(kripke_builder.transitions().count(), 4);
+    assert_eq!(
+        kripke_builder.successors(&0).collect::<Vec<_>>(),
+        vec![(&1, None), (&1, Some("go"))]
+    );
+    assert_eq!(
+        kripke_builder.predecessors(&1).collect::<Vec<_>>(),
+        vec![(&0, None), (&0, Some("go"))]
+    );
+
+    assert!(kripke_builder.remove_labelled_transition(&0, &1, "go"));
+    assert!(!kripke_builder.remove_labelled_transition(&0, &1, "go"));
+    assert!(kripke_builder.set_aps(&1, vec!["b".to_string()]));
+    assert_eq!(kripke_builder.aps(&1), Some(&["b".to_string()][..]));
+    assert!(kripke_builder.set_start(&1, true));
+    assert!(!kripke_builder.set_start(&7, true));
+
+    assert!(kripke_builder.remove_state(&2));
+    assert!(!kripke_builder.contains_state(&2));
+    assert_eq!(kripke_builder.amount_states(), 2);
+    assert_eq!(
+        kripke_builder
+            .transitions()
+            .map(|(from, to, _)| (*from, *to))
+            .collect::<Vec<_>>(),
+        vec![(0, 1)]
+    );
+    assert_eq!(
+        kripke_builder
+            .states()
+            .map(|(id, _, start)| (*id, start))
+            .collect::<Vec<_>>(),
+        vec![(0, true), (1, true)]
+    );
+
+    // Transitions may be added before their states.
+    kripke_builder.add_transition(3, 1);
+    assert!(!kripke_builder.validate().is_empty());
+    kripke_builder.add_state(vec![], 3, false);
+    assert_eq!(kripke_builder.successors(&3).count(), 1);
+    kripke_builder.add_transition(1, 1);
+    assert!(kripke_builder.validate().is_empty());
+}
+
+#[test]
+fn test_transition_system() {
+    use model_checker::{ltl_model_check_system, DeadlockPolicy, TransitionSystem};
+
+    /// Counter modulo `modulus` that can also be reset to zero.
+    struct Counter {
+        modulus: u32,
+    }
+
+    impl TransitionSystem for Counter {
+        type State = u32;
+
+        fn initial_states(&self) -> Vec<u32> {
+            vec![0]
+        }
+
+        fn successors(&self, state: &u32) -> Vec<u32> {
+            vec![(state + 1) % self.modulus, 0]
+        }
+
+        fn labels(&self, state: &u32) -> Vec<String> {
+            let mut labels = vec![];
+            if *state == 0 {
+                labels.push("zero".to_string());
+            }
+            if *state == self.modulus - 1 {
+                labels.push("max".to_string());
+            }
+            labels
+        }
+    }
+
+    let counter = Counter { modulus: 5 };
+    assert!(ltl_model_check_system(&counter, "G(!max | X zero)")
+        .unwrap()
+        .is_none());
+    let counterexample = ltl_model_check_system(&counter, "G F max")
+        .unwrap()
+        .unwrap();
+    assert!(!counterexample.cycle().contains(&4));
+    assert!(ltl_model_check_system(&counter, "G !max")
+        .unwrap()
+        .unwrap()
+        .states()
+        .any(|state| *state == 4));
+
+    // KripkeBuilder is checked the same way on the fly.
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["idle".to_string()], 0, true);
+    kripke_builder.add_state(vec!["waiting".to_string()], 1, false);
+    kripke_builder.add_state(vec![], 2, false);
+    kripke_builder.add_labelled_transition(0, 1, "send".to_string());
+    kripke_builder.add_labelled_transition(1, 0, "ack".to_string());
+    kripke_builder.add_labelled_transition(1, 2, "fail".to_string());
+    for policy in [
+        DeadlockPolicy::Stutter,
+        DeadlockPolicy::DeadSink,
+        DeadlockPolicy::Finite,
+    ] {
+        kripke_builder.set_deadlock_policy(policy);
+        for formula in [
+            "G(!send | waiting)",
+            "G(!send | F ack)",
+            "G F idle | F fail",
+            "F G !waiting",
+            "X X (idle | !idle)",
+        ] {
+            assert_eq!(
+                ltl_model_check_system(&kripke_builder, formula)
+                    .unwrap()
+                    .is_some(),
+                model_checker::ltl_model_check(kripke_builder.clone(), formula)
+                    .unwrap()
+                    .is_some(),
+                "{:?} {}",
+                policy,
+                formula
+            );
+        }
+    }
+    kripke_builder.set_deadlock_policy(DeadlockPolicy::Error);
+    assert_eq!(
+        ltl_model_check_system(&kripke_builder, "G idle")
+            .unwrap_err()
+            .kind(),
+        &model_checker::ModelCheckingErrorKind::Deadlock(2)
+    );
+}
+
+#[test]
+fn test_composition() {
+    use model_checker::ModelCheckingErrorKind;
+
+    let mut sender = KripkeBuilder::new();
+    sender.add_state(vec!["ready".to_string()], 0, true);
+    sender.add_state(vec!["sent".to_string()], 1, false);
+    sender.add_labelled_transition(0, 1, "send".to_string());
+    sender.add_labelled_transition(1, 0, "reset".to_string());
+
+    let mut receiver = KripkeBuilder::new();
+    receiver.add_state(vec!["ready".to_string()], 0, true);
+    receiver.add_state(vec!["got".to_string()], 1, false);
+    receiver.add_labelled_transition(0, 1, "send".to_string());
+    receiver.add_labelled_transition(1, 0, "consume".to_string());
+
+    let components = [sender, receiver];
+    let joint_send = "G(!send | sent & got)";
+
+    let interleaved = model_checker::compose_asynchronous(&components, true).unwrap();
+    assert_eq!(interleaved.amount_states(), 4);
+    assert_eq!(interleaved.aps(&vec![0, 0]).unwrap(), ["ready".to_string()]);
+    assert_eq!(
+        interleaved.aps(&vec![1, 1]).unwrap(),
+        ["sent".to_string(), "got".to_string()]
+    );
+    assert_eq!(
+        interleaved.successors(&vec![0, 0]).collect::<Vec<_>>(),
+        vec![(&vec![1, 1], Some("send"))]
+    );
+    assert!(model_checker::ltl_model_check(interleaved, joint_send)
+        .unwrap()
+        .is_none());
+
+    let unsynchronised = model_checker::compose_asynchronous(&components, false).unwrap();
+    assert_eq!(unsynchronised.successors(&vec![0, 0]).count(), 2);
+    assert!(model_checker::ltl_model_check(unsynchronised, joint_send)
+        .unwrap()
+        .is_some());
+
+    let synchronous = model_checker::compose_synchronous(&components, true).unwrap();
+    assert_eq!(synchronous.amount_states(), 2);
+    assert_eq!(
+        synchronous.successors(&vec![1, 1]).collect::<Vec<_>>(),
+        vec![(&vec![0, 0], Some("reset")), (&vec![0, 0], Some("consume"))]
+    );
+    assert!(
+        model_checker::ltl_model_check(synchronous.clone(), "G(ready | send)")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        model_checker::ltl_model_check(synchronous, "G(!send | X(reset | consume))")
+            .unwrap()
+            .is_none()
+    );
+
+    let mut invalid = KripkeBuilder::new();
+    invalid.add_state(vec![], 0, false);
+    assert_eq!(
+        model_checker::compose_synchronous(&[invalid], false)
+            .unwrap_err()
+            .kind(),
+        &ModelCheckingErrorKind::ModelNoStart
+    );
+}
+
+#[test]
+fn test_minimisation() {
+    use model_checker::{Counterexample, Equivalence, ModelCheckingErrorKind, Quotient};
+
+    fn assert_path(ks: &KripkeBuilder, counterexample: &Counterexample) {
+        let states: Vec<u64> = counterexample
+            .states()
+            .chain(counterexample.cycle().first())
+            .copied()
+            .collect();
+        assert!(ks.is_start(&states[0]).unwrap());
+        for step in states.windows(2) {
+            assert!(ks.successors(&step[0]).any(|(to, _)| *to == step[1]));
+        }
+    }
+
+    let mut ring = KripkeBuilder::new();
+    for state in 0..6 {
+        let aps = if state % 2 == 0 {
+            vec!["p".to_string()]
+        } else {
+            vec![]
+        };
+        ring.add_state(aps, state, state == 0);
+        ring.add_transition(state, (state + 1) % 6);
+    }
+    ring.add_state(vec!["q".to_string()], 6, false);
+    ring.add_transition(4, 6);
+    ring.add_transition(6, 0);
+
+    let quotient = Quotient::new(&ring, "G F p", Equivalence::Bisimulation).unwrap();
+    assert_eq!(quotient.model().amount_states(), 2);
+    assert_eq!(quotient.states_of(0), [0, 2, 4]);
+    assert_eq!(
+        Quotient::new(&ring, "G F q", Equivalence::Bisimulation)
+            .unwrap()
+            .model()
+            .amount_states(),
+        7
+    );
+    for formula in ["G F p", "G p", "G(p | X p)", "F G !q", "G(q | p | X p)"] {
+        let expected = model_checker::ltl_model_check(ring.clone(), formula).unwrap();
+        let result = model_checker::ltl_model_check_minimised(
+            ring.clone(),
+            formula,
+            Equivalence::Bisimulation,
+        )
+        .unwrap();
+        assert_eq!(result.is_some(), expected.is_some(), "{}", formula);
+        if let Some(counterexample) = result {
+            assert_path(&ring, &counterexample);
+        }
+    }
+
+    let mut stuttering = KripkeBuilder::new();
+    stuttering.add_state(vec!["a".to_string()], 0, true);
+    stuttering.add_state(vec!["a".to_string()], 1, false);
+    stuttering.add_state(vec!["a".to_string()], 2, false);
+    stuttering.add_state(vec!["b".to_string()], 3, false);
+    stuttering.add_transition(0, 1);
+    stuttering.add_transition(1, 2);
+    stuttering.add_transition(2, 3);
+    stuttering.add_transition(3, 3);
+    let quotient = Quotient::new(&stuttering, "F b", Equivalence::StutteringBisimulation).unwrap();
+    assert_eq!(quotient.model().amount_states(), 2);
+    assert!(model_checker::ltl_model_check_minimised(
+        stuttering.clone(),
+        "F b",
+        Equivalence::StutteringBisimulation
+    )
+    .unwrap()
+    .is_none());
+    let counterexample = model_checker::ltl_model_check_minimised(
+        stuttering.clone(),
+        "G a",
+        Equivalence::StutteringBisimulation,
+    )
+    .unwrap()
+    .unwrap();
+    assert_path(&stuttering, &counterexample);
+    assert_eq!(counterexample.prefix(), [0, 1, 2]);
+    assert_eq!(counterexample.cycle(), [3]);
+    assert_eq!(
+        model_checker::ltl_model_check_minimised(
+            stuttering.clone(),
+            "X b",
+            Equivalence::StutteringBisimulation
+        )
+        .unwrap_err()
+        .kind(),
+        &ModelCheckingErrorKind::FormulaContainsNext
+    );
+
+    // Staying in 2 forever must not be merged away.
+    stuttering.add_transition(2, 2);
+    let counterexample = model_checker::ltl_model_check_minimised(
+        stuttering.clone(),
+        "F b",
+        Equivalence::StutteringBisimulation,
+    )
+    .unwrap()
+    .unwrap();
+    assert_path(&stuttering, &counterexample);
+    assert_eq!(counterexample.cycle(), [2]);
+
+    let mut actions = KripkeBuilder::new();
+    actions.add_state(vec![], 0, true);
+    actions.add_state(vec![], 1, false);
+    actions.add_labelled_transition(0, 1, "go".to_string());
+    actions.add_labelled_transition(0, 1, "wait".to_string());
+    actions.add_labelled_transition(1, 0, "back".to_string());
+    for formula in ["G F go", "G(back | go | wait | X 1)", "G F(go | wait)"] {
+        for equivalence in [
+            Equivalence::Bisimulation,
+            Equivalence::StutteringBisimulation,
+        ] {
+            if equivalence == Equivalence::StutteringBisimulation && formula.contains('X') {
+                continue;
+            }
+            let expected = model_checker::ltl_model_check(actions.clone(), formula).unwrap();
+            let result =
+                model_checker::ltl_model_check_minimised(actions.clone(), formula, equivalence)
+                    .unwrap();
+            assert_eq!(result.is_some(), expected.is_some(), "{}", formula);
+            if let Some(counterexample) = result {
+                assert_path(&actions, &counterexample);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_formula_automaton_statistics() {
+    let statistics = model_checker::formula_automaton_statistics("G F a & G F b").unwrap();
+    assert!(statistics.states_after < statistics.states_before);
+    assert!(statistics.transitions_after < statistics.transitions_before);
+    assert_eq!(
+        statistics.states_before - statistics.useless_states - statistics.merged_states,
+        statistics.states_after
+    );
+    assert!(model_checker::formula_automaton_statistics("G (a").is_err());
+}
+
+#[test]
+fn test_translate_formula() {
+    use model_checker::{Dialect, ModelCheckingErrorKind};
+
+    let spot = "[](request -> <>grant) && (a W b)";
+    let nusmv = model_checker::translate_formula(spot, Dialect::Spot, Dialect::NuSMV).unwrap();
+    assert_eq!(nusmv, "G (request -> F grant) & (a U b | G a)");
+    let native = model_checker::translate_formula(&nusmv, Dialect::NuSMV, Dialect::Native).unwrap();
+    assert_eq!(native, "G (request -> F grant) & ((a U b) | G a)");
+    assert_eq!(
+        model_checker::translate_formula("F \"x>3\"", Dialect::Native, Dialect::NuSMV)
+            .unwrap_err()
+            .kind(),
+        &ModelCheckingErrorKind::FormulaAPNotExpressible("x>3".to_string())
+    );
+}
+
+#[test]
+fn test_formula_builder() {
+    use model_checker::{always, ap, eventually, ModelCheckingErrorKind};
+    use std::collections::HashMap;
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["req".to_string()], 0, true);
+    kripke_builder.add_state(vec!["grant".to_string()], 1, false);
+    kripke_builder.add_state(vec![], 2, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(1, 2);
+    kripke_builder.add_transition(2, 0);
+    kripke_builder.add_transition(2, 2);
+
+    let response = always(ap("req").implies(eventually(ap("grant"))));
+    let (ltl, ap_map) = response.into_parts();
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_none()
+    );
+    let (ltl, ap_map) = (always(eventually(ap("req"))) | !ap("req")).into_parts();
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_some()
+    );
+
+    let ap_map = HashMap::from([("req".to_string(), 0)]);
+    let ltl = model_checker::LTLFormula::and(
+        model_checker::LTLFormula::ap(0),
+        model_checker::LTLFormula::ap(1),
+    );
+    assert_eq!(
+        model_checker::ltl_model_check_formula(kripke_builder, &ltl, &ap_map)
+            .unwrap_err()
+            .kind(),
+        &ModelCheckingErrorKind::FormulaUnnamedAP(1)
+    );
+}
+
+#[test]
+fn test_unused_propositions() {
+    use model_checker::{ap, next, LTLFormula};
+    use std::collections::HashMap;
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["req".to_string(), "a".to_string()], 0, true);
+    kripke_builder.add_state(vec!["grant".to_string()], 1, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(1, 0);
+
+    // `req` is named but does not occur in the formula.
+    let ap_map = HashMap::from([("req".to_string(), 0), ("grant".to_string(), 1)]);
+    let ltl = LTLFormula::always(LTLFormula::next(LTLFormula::ap(1)));
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_some()
+    );
+    let ltl = LTLFormula::ap(1);
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_some()
+    );
+    let ltl = LTLFormula::always(LTLFormula::implies(
+        LTLFormula::ap(0),
+        LTLFormula::next(LTLFormula::ap(1)),
+    ));
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_none()
+    );
+
+    // `a -> a` is encoded with `b`, so `a` no longer occurs.
+    let (ltl, ap_map) = (ap("b") | next(ap("a").implies(ap("a")))).into_parts();
+    assert_eq!(ap_map, HashMap::from([("b".to_string(), 0)]));
+    assert!(
+        model_checker::ltl_model_check_formula(kripke_builder, &ltl, &ap_map)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_specification() {
+    use model_checker::Specification;
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["req".to_string()], 0, true);
+    kripke_builder.add_state(vec!["busy".to_string()], 1, false);
+    kripke_builder.add_state(vec!["grant".to_string()], 2, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(1, 2);
+    kripke_builder.add_transition(2, 0);
+
+    let specification = Specification::parse(
+        "let idle = !req & !busy;
+        let responds(p, q) = G(p -> F q);
+        property progress: responds(req, grant);
+        property quiet: F G idle;",
+    )
+    .unwrap();
+    let results = specification.check(&kripke_builder);
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, "progress");
+    assert!(results[0].1.as_ref().unwrap().is_none());
+    assert_eq!(results[1].0, "quiet");
+    assert!(results[1].1.as_ref().unwrap().is_some());
+}
+
+#[test]
+fn test_ltlf() {
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["req".to_string()], 0, true);
+    kripke_builder.add_state(vec!["busy".to_string()], 1, false);
+    kripke_builder.add_state(vec!["grant".to_string()], 2, false);
+    kripke_builder.add_transition(0, 1);
+    kripke_builder.add_transition(1, 2);
+    kripke_builder.add_transition(1, 1);
+    kripke_builder.add_transition(2, 0);
+    kripke_builder.set_final(&2, true);
+    assert_eq!(kripke_builder.is_final(&2), Some(true));
+    assert_eq!(kripke_builder.is_final(&1), Some(false));
+
+    // Every path ends with a grant, although the infinite path 0 -> 1 -> 1 ... never gets one.
+    assert!(
+        model_checker::ltlf_model_check(kripke_builder.clone(), "G (req -> F grant)")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        model_checker::ltl_model_check(kripke_builder.clone(), "G (req -> F grant)")
+            .unwrap()
+            .is_some()
+    );
+    // The last state has no next state.
+    let counterexample =
+        model_checker::ltlf_model_check(kripke_builder.clone(), "G (grant -> X req)")
+            .unwrap()
+            .unwrap();
+    assert_eq!(counterexample.prefix(), &[0, 1, 2]);
+    assert!(counterexample.cycle().is_empty());
+    assert_eq!(counterexample.deadlock(), None);
+    assert!(model_checker::ltlf_model_check(
+        kripke_builder,
+        "G (grant -> WX req) & G (busy -> X (busy | grant))"
+    )
+    .unwrap()
+    .is_none());
+}
+
+#[test]
+fn test_monitor() {
+    use model_checker::{ap, eventually, Monitor, Verdict};
+
+    let trace = [
+        vec!["req"],
+        vec!["busy"],
+        vec!["grant", "req"],
+        vec!["error"],
+    ];
+    let mut response = Monitor::from_formula(&ap("req").implies(eventually(ap("grant"))).always());
+    let mut safety = Monitor::new("G !error").unwrap();
+    let verdicts: Vec<(Verdict, Verdict)> = trace
+        .iter()
+        .map(|step| (response.step(step), safety.step(step)))
+        .collect();
+    assert_eq!(
+        verdicts,
+        vec![
+            (Verdict::Inconclusive, Verdict::Inconclusive),
+            (Verdict::Inconclusive, Verdict::Inconclusive),
+            (Verdict::Inconclusive, Verdict::Inconclusive),
+            (Verdict::Inconclusive, Verdict::Violated)
+        ]
+    );
+    assert_eq!(safety.verdict(), Verdict::Violated);
+}
+
+#[test]
+fn test_large_bounds() {
+    use model_checker::{ModelCheckingErrorKind, ParsingErrorKind};
+    use std::time::{Duration, Instant};
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, true);
+    kripke_builder.add_transition(0, 0);
+
+    for formula in ["X[64] a", "F[0,16] a", "G(a -> X[12] a)"] {
+        let start = Instant::now();
+        let error = model_checker::ltl_model_check(kripke_builder.clone(), formula).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(1), "{formula}");
+        let ModelCheckingErrorKind::FormulaSytaxError(error) = error.kind() else {
+            panic!("{formula}")
+        };
+        assert_eq!(error.kind(), &ParsingErrorKind::BoundTooLarge, "{formula}");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    use model_checker::{Counterexample, DeadlockPolicy, ModelCheckingError};
+
+    let mut kripke_builder = KripkeBuilder::new();
+    kripke_builder.add_state(vec!["a".to_string()], 0, true);
+    kripke_builder.add_state(vec!["b".to_string()], 1, false);
+    kripke_builder.add_labelled_transition(0, 1, "go".to_string());
+    kripke_builder.add_transition(1, 2);
+    kripke_builder.set_deadlock_policy(DeadlockPolicy::Stutter);
+    let json = serde_json::to_string(&kripke_builder).unwrap();
+    let deserialized: KripkeBuilder = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, kripke_builder);
+
+    let mut tuple_builder = KripkeBuilder::<(String, u8)>::new();
+    tuple_builder.add_state(vec![], ("p".to_string(), 0), true);
+    tuple_builder.add_transition(("p".to_string(), 0), ("p".to_string(), 0));
+    let json = serde_json::to_string(&tuple_builder).unwrap();
+    let deserialized: KripkeBuilder<(String, u8)> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, tuple_builder);
+    assert!(model_checker::ltl_model_check(deserialized, "G !a")
+        .unwrap()
+        .is_none());
+
+    let errors = vec![
+        model_checker::ltl_model_check(kripke_builder.clone(), "G a").unwrap_err(),
+        model_checker::ltl_model_check(kripke_builder.clone(), "G (a").unwrap_err(),
+        model_checker::ltl_model_check_program("var x: 0..2 init 3;", "x = 0").unwrap_err(),
+    ];
+    for error in errors {
+        let json = serde_json::to_string(&error).unwrap();
+        let deserialized: ModelCheckingError = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, error);
+    }
+
+    kripke_builder.add_state(vec![], 2, false);
+    let counterexample = model_checker::ltl_model_check(kripke_builder, "G a")
+        .unwrap()
+        .unwrap();
+    let json = serde_json::to_string(&counterexample).unwrap();
+    let deserialized: Counterexample = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, counterexample);
 }
@@ -0,0 +1,171 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, token, Ident, LitStr, Token};
+
+/// Label or action, which is an identifier or a string literal.
+struct Name(String);
+
+impl Parse for Name {
+    fn parse(input: ParseStream) -> syn::Result<Name> {
+        if input.peek(LitStr) {
+            Ok(Name(input.parse::<LitStr>()?.value()))
+        } else {
+            Ok(Name(input.parse::<Ident>()?.to_string()))
+        }
+    }
+}
+
+/// `state [init] {labels} -> target [action], ...;`
+struct Statement {
+    state: Ident,
+    start: bool,
+    labels: Vec<Name>,
+    targets: Vec<(Ident, Option<Name>)>,
+}
+
+impl Parse for Statement {
+    fn parse(input: ParseStream) -> syn::Result<Statement> {
+        let state: Ident = input.parse()?;
+        let mut start = false;
+        if input.peek(token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            let attribute: Ident = content.parse()?;
+            if attribute != "init" {
+                return Err(syn::Error::new(attribute.span(), "expected `init`"));
+            }
+            start = true;
+        }
+        let mut labels = vec![];
+        if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            labels = content
+                .parse_terminated(Name::parse, Token![,])?
+                .into_iter()
+                .collect();
+        }
+        let mut targets = vec![];
+        if input.peek(Token![->]) {
+            input.parse::<Token![->]>()?;
+            loop {
+                let target: Ident = input.parse()?;
+                let mut action = None;
+                if input.peek(token::Bracket) {
+                    let content;
+                    bracketed!(content in input);
+                    action = Some(content.parse()?);
+                }
+                targets.push((target, action));
+                if input.parse::<Option<Token![,]>>()?.is_none() {
+                    break;
+                }
+            }
+        }
+        input.parse::<Token![;]>()?;
+        Ok(Statement {
+            state,
+            start,
+            labels,
+            targets,
+        })
+    }
+}
+
+struct Model(Vec<Statement>);
+
+impl Parse for Model {
+    fn parse(input: ParseStream) -> syn::Result<Model> {
+        let mut statements = vec![];
+        while !input.is_empty() {
+            statements.push(input.parse()?);
+        }
+        Ok(Model(statements))
+    }
+}
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let Model(statements) = syn::parse2(input)?;
+
+    let mut declared = HashMap::<String, &Ident>::new();
+    for statement in &statements {
+        if declared
+            .insert(statement.state.to_string(), &statement.state)
+            .is_some()
+        {
+            return Err(syn::Error::new(
+                statement.state.span(),
+                format!("state `{}` is declared twice", statement.state),
+            ));
+        }
+    }
+    for (target, _) in statements.iter().flat_map(|statement| &statement.targets) {
+        if !declared.contains_key(&target.to_string()) {
+            return Err(syn::Error::new(
+                target.span(),
+                format!("unknown state `{}`", target),
+            ));
+        }
+    }
+    if !statements.iter().any(|statement| statement.start) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "model has no start state, mark one with `[init]`",
+        ));
+    }
+
+    let states = statements.iter().map(|statement| {
+        let state = statement.state.to_string();
+        let labels = statement.labels.iter().map(|Name(label)| label);
+        let start = statement.start;
+        quote! {
+            kripke_builder.add_state(vec![#(#labels.to_string()),*], #state.to_string(), #start);
+        }
+    });
+    let transitions = statements.iter().flat_map(|statement| {
+        let from = statement.state.to_string();
+        statement.targets.iter().map(move |(to, action)| {
+            let to = to.to_string();
+            match action {
+                Some(Name(action)) => quote! {
+                    kripke_builder.add_labelled_transition(
+                        #from.to_string(),
+                        #to.to_string(),
+                        #action.to_string(),
+                    );
+                },
+                None => quote! {
+                    kripke_builder.add_transition(#from.to_string(), #to.to_string());
+                },
+            }
+        })
+    });
+    Ok(quote! {
+        {
+            let mut kripke_builder = ::model_checker::KripkeBuilder::<String>::new();
+            #(#states)*
+            #(#transitions)*
+            kripke_builder
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors() {
+        let error = |input: TokenStream| expand(input).unwrap_err().to_string();
+        assert_eq!(error(quote!(a [init] -> b;)), "unknown state `b`");
+        assert_eq!(error(quote!(a [init]; a;)), "state `a` is declared twice");
+        assert_eq!(
+            error(quote!(a -> a;)),
+            "model has no start state, mark one with `[init]`"
+        );
+        assert_eq!(error(quote!(a [start];)), "expected `init`");
+        assert!(expand(quote!(a [init] {x, "y.z"} -> a [go], b; b;)).is_ok());
+    }
+}
@@ -0,0 +1,44 @@
+//! Macros that check formulas and models of `model_checker` at compile time.
+
+use proc_macro::TokenStream;
+
+mod kripke;
+mod ltl;
+
+/// Parses an LTL formula at compile time and builds a `model_checker::Formula` from it. Syntax
+/// errors are reported at the formula.
+///
+/// ```
+/// use model_checker_macros::ltl;
+///
+/// let formula = ltl!("G(req -> F grant)");
+/// assert_eq!(formula.to_string(), "G (req -> F grant)");
+/// ```
+#[proc_macro]
+pub fn ltl(input: TokenStream) -> TokenStream {
+    ltl::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Builds a `model_checker::KripkeBuilder<String>`, whose states are named by identifiers.
+///
+/// Every state is declared once, followed by `[init]` if it is a start state, its labels in
+/// braces and the states it leads to. A transition can carry an action in brackets. Labels and
+/// actions are identifiers or string literals.
+///
+/// ```
+/// use model_checker_macros::kripke;
+///
+/// let model = kripke! {
+///     idle [init] {ready} -> busy [start];
+///     busy {"door.open"} -> idle, busy;
+/// };
+/// assert_eq!(model.amount_states(), 2);
+/// ```
+#[proc_macro]
+pub fn kripke(input: TokenStream) -> TokenStream {
+    kripke::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,87 @@
+use model_checker::{Formula, LTLFormula, ModelCheckingErrorKind};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::LitStr;
+
+pub fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let literal: LitStr = syn::parse2(input)?;
+    let text = literal.value();
+    let formula = Formula::parse(&text).map_err(|error| {
+        let span = match error.kind() {
+            ModelCheckingErrorKind::FormulaSytaxError(error) => error
+                .span()
+                .and_then(|span| subspan(&literal, &text, span.start, span.end)),
+            _ => None,
+        };
+        syn::Error::new(span.unwrap_or(literal.span()), error)
+    })?;
+
+    let (ltl, ap_map) = formula.into_parts();
+    let ltl = formula_tokens(&ltl);
+    let mut names: Vec<(String, u8)> = ap_map.into_iter().collect();
+    names.sort_by_key(|(_, ap)| *ap);
+    let names = names
+        .iter()
+        .map(|(name, ap)| quote!((#name.to_string(), #ap)));
+    Ok(quote! {
+        ::model_checker::Formula::from_parts(
+            #ltl,
+            ::std::collections::HashMap::from([#(#names),*]),
+        )
+    })
+}
+
+/// Span of the characters `start..end` of the formula. Only available on compilers that
+/// support spans inside literals, and only for literals without escapes.
+fn subspan(literal: &LitStr, text: &str, start: usize, end: usize) -> Option<Span> {
+    if literal.token().to_string() != format!("\"{}\"", text) {
+        return None;
+    }
+    let byte = |index: usize| {
+        text.char_indices()
+            .map(|(byte, _)| byte)
+            .chain([text.len()])
+            .nth(index)
+    };
+    // The literal starts with its quote.
+    let (start, end) = (byte(start)? + 1, byte(end.max(start + 1))? + 1);
+    literal.token().subspan(start..end)
+}
+
+fn formula_tokens(ltl: &LTLFormula) -> TokenStream {
+    match ltl {
+        LTLFormula::AP(ap) => quote!(::model_checker::LTLFormula::ap(#ap)),
+        LTLFormula::Not(phi) => {
+            let phi = formula_tokens(phi);
+            quote!(::model_checker::LTLFormula::not(#phi))
+        }
+        LTLFormula::And(phi1, phi2) => {
+            let (phi1, phi2) = (formula_tokens(phi1), formula_tokens(phi2));
+            quote!(::model_checker::LTLFormula::and(#phi1, #phi2))
+        }
+        LTLFormula::Next(phi) => {
+            let phi = formula_tokens(phi);
+            quote!(::model_checker::LTLFormula::next(#phi))
+        }
+        LTLFormula::Until(weak, phi1, phi2) => {
+            let (phi1, phi2) = (formula_tokens(phi1), formula_tokens(phi2));
+            quote!(::model_checker::LTLFormula::until(#phi1, #phi2, #weak))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors() {
+        let error = expand(quote!("G(a &)")).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Operator '&' is missing its operand"));
+        assert!(expand(quote!("G 1")).is_err());
+        assert!(expand(quote!(G(a))).is_err());
+        assert!(expand(quote!("G(a -> F b)")).is_ok());
+    }
+}
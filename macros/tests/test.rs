@@ -0,0 +1,36 @@
+use model_checker_macros::{kripke, ltl};
+
+#[test]
+fn test_ltl() {
+    let formula = ltl!("G(req -> F grant) & \"door.open\" W b");
+    assert_eq!(
+        formula.to_string(),
+        model_checker::Formula::parse("G(req -> F grant) & \"door.open\" W b")
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(formula.ap_map().len(), 4);
+}
+
+#[test]
+fn test_kripke() {
+    let model = kripke! {
+        idle [init] {ready} -> busy [start];
+        busy {"door.open"} -> idle, busy;
+    };
+    assert_eq!(model.amount_states(), 2);
+    assert_eq!(model.aps(&"busy".to_string()).unwrap(), ["door.open"]);
+    assert!(model.is_start(&"idle".to_string()).unwrap());
+
+    let (ltl, ap_map) = ltl!("G(ready -> X start)").into_parts();
+    assert!(
+        model_checker::ltl_model_check_formula(model.clone(), &ltl, &ap_map)
+            .unwrap()
+            .is_none()
+    );
+    let (ltl, ap_map) = ltl!("G F ready").into_parts();
+    let counterexample = model_checker::ltl_model_check_formula(model, &ltl, &ap_map)
+        .unwrap()
+        .unwrap();
+    assert_eq!(counterexample.cycle(), ["busy".to_string()]);
+}
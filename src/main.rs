@@ -6,8 +6,10 @@ use eframe::egui;
 use eframe::egui::{Align, Layout, Window};
 use eframe::emath::{vec2, Pos2, Vec2};
 use eframe::epaint::{CubicBezierShape, Stroke};
-use egui::{Align2, Color32, FontId, Frame, Key, PointerButton, Sense, Shape, Ui};
-use model_checker::{ltl_model_check, KripkeBuilder};
+use egui::{
+    Align2, Color32, ComboBox, FontId, Frame, Key, PointerButton, RichText, Sense, Shape, Ui,
+};
+use model_checker::{formula_diagnostics, lint, ltl_model_check, DeadlockPolicy, KripkeBuilder};
 
 type StateId = u64;
 
@@ -56,16 +58,9 @@ const STATE_RADIUS: f32 = 20.0;
 const ARROWHEAD_HALF_WIDTH: f32 = 6.0;
 const ARROWHEAD_LENGTH: f32 = 12.0;
 
-struct MyState {
-    aps: Vec<String>,
-    pos: Pos2,
-    id: StateId,
-    start: bool,
-}
-
 struct MyApp {
-    states: HashMap<StateId, MyState>,
-    transitions: Vec<(StateId, StateId)>,
+    model: KripkeBuilder<StateId>,
+    positions: HashMap<StateId, Pos2>,
     current_id: StateId,
     start_drag: Option<StateId>,
     selected_id: Option<StateId>,
@@ -73,13 +68,14 @@ struct MyApp {
     aptext: String,
     result_text: String,
     show_help: bool,
+    deadlock_policy: DeadlockPolicy,
 }
 
 impl Default for MyApp {
     fn default() -> Self {
         Self {
-            states: HashMap::new(),
-            transitions: vec![],
+            model: KripkeBuilder::new(),
+            positions: HashMap::new(),
             current_id: 0,
             start_drag: None,
             selected_id: None,
@@ -87,34 +83,43 @@ impl Default for MyApp {
             aptext: "".to_owned(),
             result_text: "".to_owned(),
             show_help: false,
+            deadlock_policy: DeadlockPolicy::default(),
         }
     }
 }
 
 impl MyApp {
     fn check(&mut self) {
-        let mut kripke_builder = KripkeBuilder::new();
-
-        for state in self.states.values() {
-            kripke_builder.add_state(state.aps.clone(), state.id, state.start);
-        }
-        for (state1, state2) in &self.transitions {
-            kripke_builder.add_transition(*state1, *state2);
+        let diagnostics = formula_diagnostics(&self.query);
+        if !diagnostics.is_empty() {
+            self.result_text = diagnostics
+                .iter()
+                .map(|error| format!("⚠ {}", error))
+                .collect::<Vec<String>>()
+                .join("\n");
+            return;
         }
 
+        let mut kripke_builder = self.model.clone();
+        kripke_builder.set_deadlock_policy(self.deadlock_policy);
+
+        let warnings = lint(&kripke_builder, &self.query).unwrap_or_default();
         let result = ltl_model_check(kripke_builder, &self.query);
 
         if let Ok(result) = result {
-            if result.is_none() {
-                self.result_text = "✔ Erfüllt".to_owned();
+            if let Some(counterexample) = result {
+                self.result_text = format!("❌ Fehler! Gegenbeispiel: {}", counterexample);
             } else {
-                self.result_text = "❌ Fehler!".to_owned();
+                self.result_text = "✔ Erfüllt".to_owned();
             }
         } else {
             let mut error = result.unwrap_err().to_string();
             error.insert_str(0, "⚠ ");
             self.result_text = error;
         }
+        for warning in warnings {
+            self.result_text.push_str(&format!("\nℹ {}", warning));
+        }
     }
 
     fn my_update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame, ui: &mut Ui) {
@@ -128,7 +133,28 @@ impl MyApp {
                     if button.clicked() {
                         self.check();
                     }
-                    ui.label(&self.result_text);
+                    // Monospace, so that the caret of syntax errors lines up with the formula.
+                    ui.label(RichText::new(&self.result_text).monospace());
+                });
+                ui.horizontal(|ui| {
+                    let policies = [
+                        (DeadlockPolicy::Error, "Fehler"),
+                        (DeadlockPolicy::Stutter, "Zustand wiederholen"),
+                        (DeadlockPolicy::DeadSink, "Senke"),
+                        (DeadlockPolicy::Finite, "Endliche Pfade (LTLf)"),
+                    ];
+                    let selected = policies
+                        .iter()
+                        .find(|(policy, _)| *policy == self.deadlock_policy)
+                        .unwrap()
+                        .1;
+                    ComboBox::from_label("Deadlocks")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (policy, text) in policies {
+                                ui.selectable_value(&mut self.deadlock_policy, policy, text);
+                            }
+                        });
                 });
                 ui.horizontal(|ui| {
                     let name_label = ui.label("APs: ");
@@ -137,13 +163,13 @@ impl MyApp {
                     let button = ui.button("Setzen");
                     if button.clicked() {
                         if let Some(selected_id) = self.selected_id {
-                            let state = self.states.get_mut(&selected_id).unwrap();
-                            state.aps = self
+                            let aps = self
                                 .aptext
                                 .split(",")
                                 .filter(|s| !s.trim().is_empty())
                                 .map(|a| a.trim().to_owned())
                                 .collect();
+                            self.model.set_aps(&selected_id, aps);
                         }
                     }
                 });
@@ -183,13 +209,29 @@ impl MyApp {
                     ui.code("&");
                     ui.label(", Or ");
                     ui.code("|");
+                    ui.label(", Implies ");
+                    ui.code("->");
+                    ui.label(", Equivalence ");
+                    ui.code("<->");
                     ui.label(", Not ");
                     ui.code("!");
                     ui.label(", true ");
                     ui.code("1");
                     ui.label(", false ");
                     ui.code("0");
-                    ui.label(", parenthesis and atomic propositions, which begin with a lower-case letter.");
+                    ui.label(", parenthesis and atomic propositions. Propositions are names like `ready`, `door.open` or `robot::arm`, which may begin with an upper-case letter unless it is an operator, or quoted like `\"x>3\"`. The Unicode symbols → ↔ ∧ ∨ ¬ □ ◇ ○ can be used as well.");
+                });
+                ui.add_space(10.0);
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Bounded operators such as ");
+                    ui.code("F[0,3] grant");
+                    ui.label(", ");
+                    ui.code("G[2,5] safe");
+                    ui.label(", ");
+                    ui.code("X[4] p");
+                    ui.label(" and ");
+                    ui.code("a U[1,2] b");
+                    ui.label(" count steps. They may unroll to at most 8 nested X in total.");
                 });
                 ui.add_space(10.0);
                 ui.label("Kripke structure states can be created with a click, connected with a drag (only visible after mouse released), deleted with `del` and made a starting state with a double click.");
@@ -210,10 +252,8 @@ impl MyApp {
             ctx.input(|input| {
                 if input.key_pressed(Key::Delete) {
                     if let Some(selected_id) = self.selected_id {
-                        self.states.remove(&selected_id).unwrap();
-                        self.transitions.retain(|(state1, state2)| {
-                            *state1 != selected_id && *state2 != selected_id
-                        });
+                        self.model.remove_state(&selected_id);
+                        self.positions.remove(&selected_id);
                         self.selected_id = None;
                         self.aptext = "".to_owned();
                     }
@@ -224,39 +264,33 @@ impl MyApp {
                 let canvas_pos = pointer_pos;
 
                 let result = self
-                    .states
-                    .values_mut()
-                    .find(|state| state.pos.distance(canvas_pos) <= STATE_RADIUS);
+                    .positions
+                    .iter()
+                    .find(|(_, pos)| pos.distance(canvas_pos) <= STATE_RADIUS)
+                    .map(|(id, _)| *id);
 
-                if let Some(hit_state) = result {
+                if let Some(hit_id) = result {
                     if response.drag_started_by(PointerButton::Primary) {
-                        self.start_drag = Some(hit_state.id);
+                        self.start_drag = Some(hit_id);
                     } else if response.drag_stopped_by(PointerButton::Primary) {
                         if let Some(start_drag) = self.start_drag {
-                            if !self.transitions.contains(&(start_drag, hit_state.id)) {
-                                self.transitions.push((start_drag, hit_state.id));
+                            if self.model.add_transition(start_drag, hit_id) {
                                 response.mark_changed();
                             }
                         }
                         self.start_drag = None;
                     } else if response.double_clicked() {
-                        hit_state.start = !hit_state.start;
+                        let start = self.model.is_start(&hit_id).unwrap();
+                        self.model.set_start(&hit_id, !start);
                     } else if response.clicked() {
-                        self.selected_id = Some(hit_state.id);
-                        self.aptext = hit_state.aps.join(", ");
+                        self.selected_id = Some(hit_id);
+                        self.aptext = self.model.aps(&hit_id).unwrap().join(", ");
                         response.mark_changed();
                     }
                 } else {
                     if response.clicked() {
-                        self.states.insert(
-                            self.current_id,
-                            MyState {
-                                aps: vec![],
-                                pos: canvas_pos,
-                                id: self.current_id,
-                                start: false,
-                            },
-                        );
+                        self.model.add_state(vec![], self.current_id, false);
+                        self.positions.insert(self.current_id, canvas_pos);
                         self.selected_id = Some(self.current_id);
                         self.aptext.clear();
                         self.current_id += 1;
@@ -273,30 +307,31 @@ impl MyApp {
 
             let shapes = ctx.fonts(|f| {
                 let mut shapes = vec![];
-                for state in self.states.values() {
-                    let stroke_color = if self.selected_id == Some(state.id) {
+                for (id, aps, start) in self.model.states() {
+                    let pos = self.positions[id];
+                    let stroke_color = if self.selected_id == Some(*id) {
                         Color32::from_rgb(0, 127, 255)
                     } else {
                         color
                     };
 
-                    if state.start {
+                    if start {
                         shapes.push(Shape::circle_filled(
-                            state.pos,
+                            pos,
                             20.0,
-                            stroke_color.clone().gamma_multiply(0.25),
+                            stroke_color.gamma_multiply(0.25),
                         ));
                     }
                     shapes.push(Shape::circle_stroke(
-                        state.pos,
+                        pos,
                         20.0,
                         Stroke::new(4.0, stroke_color),
                     ));
                     shapes.push(Shape::text(
                         f,
-                        state.pos - Vec2 { x: 25.0, y: 0.0 },
+                        pos - Vec2 { x: 25.0, y: 0.0 },
                         Align2::RIGHT_CENTER,
-                        state.aps.join("\n"),
+                        aps.join("\n"),
                         FontId::monospace(12.0),
                         color,
                     ));
@@ -306,23 +341,23 @@ impl MyApp {
 
             painter.extend(shapes);
 
-            painter.extend(self.transitions.iter().flat_map(|(id1, id2)| {
-                let state1 = self.states.get(id1).unwrap();
-                let state2 = self.states.get(id2).unwrap();
+            painter.extend(self.model.transitions().flat_map(|(id1, id2, _)| {
+                let pos1 = self.positions[id1];
+                let pos2 = self.positions[id2];
 
-                return if id1 == id2 {
+                if id1 == id2 {
                     let dir1 = vec2(1.0, -3.0).normalized();
                     let dir2 = vec2(-dir1.x, dir1.y);
                     let dir = -dir2;
                     let cross_dir = dir.rot90();
-                    let arrow_basepoint = state1.pos + dir2 * (STATE_RADIUS + ARROWHEAD_LENGTH);
+                    let arrow_basepoint = pos1 + dir2 * (STATE_RADIUS + ARROWHEAD_LENGTH);
                     [
                         Shape::CubicBezier(CubicBezierShape::from_points_stroke(
                             [
-                                state1.pos + dir1 * STATE_RADIUS,
-                                state1.pos + dir1 * STATE_RADIUS * 3.0,
-                                state1.pos + dir2 * STATE_RADIUS * 3.0,
-                                state1.pos + dir2 * STATE_RADIUS,
+                                pos1 + dir1 * STATE_RADIUS,
+                                pos1 + dir1 * STATE_RADIUS * 3.0,
+                                pos1 + dir2 * STATE_RADIUS * 3.0,
+                                pos1 + dir2 * STATE_RADIUS,
                             ],
                             false,
                             Color32::TRANSPARENT,
@@ -330,7 +365,7 @@ impl MyApp {
                         )),
                         Shape::convex_polygon(
                             vec![
-                                state1.pos - dir * STATE_RADIUS,
+                                pos1 - dir * STATE_RADIUS,
                                 arrow_basepoint + cross_dir * ARROWHEAD_HALF_WIDTH,
                                 arrow_basepoint - cross_dir * ARROWHEAD_HALF_WIDTH,
                             ],
@@ -339,17 +374,17 @@ impl MyApp {
                         ),
                     ]
                 } else {
-                    let dir = (state2.pos - state1.pos).normalized();
+                    let dir = (pos2 - pos1).normalized();
                     let cross_dir = dir.rot90();
-                    let arrow_basepoint = state2.pos - dir * (STATE_RADIUS + ARROWHEAD_LENGTH);
+                    let arrow_basepoint = pos2 - dir * (STATE_RADIUS + ARROWHEAD_LENGTH);
                     [
                         Shape::line_segment(
-                            [state1.pos + dir * STATE_RADIUS, arrow_basepoint],
+                            [pos1 + dir * STATE_RADIUS, arrow_basepoint],
                             Stroke::new(2.0, color),
                         ),
                         Shape::convex_polygon(
                             vec![
-                                state2.pos - dir * STATE_RADIUS,
+                                pos2 - dir * STATE_RADIUS,
                                 arrow_basepoint + cross_dir * ARROWHEAD_HALF_WIDTH,
                                 arrow_basepoint - cross_dir * ARROWHEAD_HALF_WIDTH,
                             ],
@@ -357,7 +392,7 @@ impl MyApp {
                             Stroke::NONE,
                         ),
                     ]
-                };
+                }
             }));
 
             response